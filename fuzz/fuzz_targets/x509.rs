@@ -0,0 +1,20 @@
+#![no_main]
+use gmssl::x509::X509;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing never verifies signatures or touches key material, so this exercises only the DER and
+// PEM decoders and the field accessors.
+fuzz_target!(|data: &[u8]| {
+    for cert in X509::from_der(data)
+        .into_iter()
+        .chain(X509::stack_from_pem(data).unwrap_or_default())
+    {
+        let _ = cert.subject_name().entries().count();
+        let _ = cert.issuer_name().entries().count();
+        let _ = cert.serial_number().to_bn();
+        let _ = cert.not_before().to_string();
+        let _ = cert.not_after().to_string();
+        let _ = cert.subject_alt_names();
+        let _ = cert.to_der();
+    }
+});
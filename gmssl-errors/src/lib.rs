@@ -50,8 +50,11 @@
 use cfg_if::cfg_if;
 use libc::{c_char, c_int};
 use std::borrow::Cow;
+use std::error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ptr;
+use std::sync::Once;
 
 #[doc(hidden)]
 pub mod export {
@@ -68,9 +71,36 @@ pub mod export {
 /// An OpenSSL error library.
 pub trait Library {
     /// Returns the ID assigned to this library by OpenSSL.
+    ///
+    /// The library and its strings are registered with OpenSSL on the first call. If OpenSSL has
+    /// no library IDs left to assign, this returns 0; use [`Library::try_id`] to detect that.
     fn id() -> c_int;
+
+    /// Returns the ID assigned to this library by OpenSSL, or an error if it could not be
+    /// registered.
+    fn try_id() -> Result<c_int, RegistrationError>
+    where
+        Self: Sized,
+    {
+        match Self::id() {
+            0 => Err(RegistrationError(())),
+            id => Ok(id),
+        }
+    }
 }
 
+/// An error indicating that OpenSSL could not assign an ID to an error library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistrationError(());
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("no error library IDs are available")
+    }
+}
+
+impl error::Error for RegistrationError {}
+
 cfg_if! {
     if #[cfg(ossl300)] {
         type FunctionInner = *const c_char;
@@ -125,6 +155,15 @@ impl<T> Reason<T> {
     }
 }
 
+/// This is not considered part of this crate's public API. It is subject to change at any time.
+///
+/// A library is only registered once its initialization has run and OpenSSL assigned it an ID.
+/// `lib_num` is only called after initialization has completed.
+#[doc(hidden)]
+pub fn __registered(init: &Once, lib_num: impl FnOnce() -> c_int) -> bool {
+    init.is_completed() && lib_num() != 0
+}
+
 /// This is not considered part of this crate's public API. It is subject to change at any time.
 ///
 /// # Safety
@@ -265,28 +304,43 @@ macro_rules! gmssl_errors {
         $(#[$lib_attr])*
         $lib_vis enum $lib_name {}
 
-        impl $crate::Library for $lib_name {
-            fn id() -> $crate::export::c_int {
-                static INIT: $crate::export::Once = $crate::export::Once::new();
-                static mut LIB_NUM: $crate::export::c_int = 0;
-                $crate::__gmssl_errors_helper! {
-                    @strings $lib_name($lib_str)
-                    functions { $($func_name($func_str);)* }
-                    reasons { $($reason_name($reason_str);)* }
-                }
+        const _: () = {
+            static INIT: $crate::export::Once = $crate::export::Once::new();
+            static mut LIB_NUM: $crate::export::c_int = 0;
+            $crate::__gmssl_errors_helper! {
+                @strings $lib_name($lib_str)
+                functions { $($func_name($func_str);)* }
+                reasons { $($reason_name($reason_str);)* }
+            }
 
-                unsafe {
-                    INIT.call_once(|| {
-                        $crate::export::init();
-                        LIB_NUM = $crate::export::ERR_get_next_error_library();
-                        STRINGS[0].error = $crate::export::ERR_PACK(LIB_NUM, 0, 0);
-                        $crate::export::ERR_load_strings(LIB_NUM, STRINGS.as_mut_ptr());
-                    });
+            impl $crate::Library for $lib_name {
+                fn id() -> $crate::export::c_int {
+                    unsafe {
+                        INIT.call_once(|| {
+                            $crate::export::init();
+                            LIB_NUM = $crate::export::ERR_get_next_error_library();
+                            if LIB_NUM != 0 {
+                                STRINGS[0].error = $crate::export::ERR_PACK(LIB_NUM, 0, 0);
+                                $crate::export::ERR_load_strings(LIB_NUM, STRINGS.as_mut_ptr());
+                            }
+                        });
 
-                    LIB_NUM
+                        LIB_NUM
+                    }
                 }
             }
-        }
+
+            impl $lib_name {
+                /// Returns `true` if the library's strings have been registered with OpenSSL.
+                ///
+                /// Registration happens lazily, the first time `id` is called (which `put_error!`
+                /// does). This returns `false` if OpenSSL had no library IDs left to assign.
+                #[allow(dead_code)]
+                pub fn registered() -> bool {
+                    $crate::__registered(&INIT, || unsafe { LIB_NUM })
+                }
+            }
+        };
 
         impl $lib_name {
             $crate::gmssl_errors!(@func_consts $lib_name; 1; $($(#[$func_attr])* $func_name($func_str);)*);
@@ -1,5 +1,7 @@
 use cfg_if::cfg_if;
 use gmssl::error::Error;
+use gmssl_errors::Library;
+use std::sync::Once;
 
 gmssl_errors::gmssl_errors! {
     library Test("test library") {
@@ -15,6 +17,18 @@ gmssl_errors::gmssl_errors! {
     }
 }
 
+gmssl_errors::gmssl_errors! {
+    library Registration("registration library") {
+        functions {
+            REGISTER("function register");
+        }
+
+        reasons {
+            NOT_YET("not registered yet");
+        }
+    }
+}
+
 #[test]
 fn basic() {
     gmssl_errors::put_error!(Test::FOO, Test::NO_MILK);
@@ -93,3 +107,43 @@ fn deferred_error_render() {
     // clear out the stack for other tests on the same thread
     while Error::get().is_some() {}
 }
+
+#[test]
+fn registered() {
+    assert!(!Registration::registered());
+
+    gmssl_errors::put_error!(Registration::REGISTER, Registration::NOT_YET);
+    assert!(Registration::registered());
+
+    let error = Error::get().unwrap();
+    assert_eq!(error.library().unwrap(), "registration library");
+    assert_eq!(error.reason().unwrap(), "not registered yet");
+}
+
+enum Exhausted {}
+
+impl Library for Exhausted {
+    fn id() -> libc::c_int {
+        0
+    }
+}
+
+#[test]
+fn try_id() {
+    let id = Test::try_id().unwrap();
+    assert_ne!(id, 0);
+    assert_eq!(id, Test::id());
+
+    assert!(Exhausted::try_id().is_err());
+}
+
+#[test]
+fn registered_requires_id() {
+    let init = Once::new();
+    assert!(!gmssl_errors::__registered(&init, || 1));
+
+    init.call_once(|| {});
+    assert!(gmssl_errors::__registered(&init, || 1));
+    // Initialization ran, but OpenSSL had no library ID to hand out.
+    assert!(!gmssl_errors::__registered(&init, || 0));
+}
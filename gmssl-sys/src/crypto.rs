@@ -1,6 +1,9 @@
 use super::*;
 use libc::*;
 
+pub const CRYPTO_R_ILLEGAL_HEX_DIGIT: c_int = 102;
+pub const CRYPTO_R_ODD_NUMBER_OF_DIGITS: c_int = 103;
+
 extern "C" {
     #[deprecated(note = "use CRYPTO_set_locking_callback__fixed_rust instead")]
     #[cfg(not(ossl110))]
@@ -5,6 +5,12 @@ use super::*;
 
 pub const OPENSSL_EC_NAMED_CURVE: c_int = 1;
 
+pub const EC_R_INVALID_ENCODING: c_int = 102;
+pub const EC_R_POINT_AT_INFINITY: c_int = 106;
+pub const EC_R_INVALID_PRIVATE_KEY: c_int = 123;
+pub const EC_R_INVALID_CURVE: c_int = 141;
+pub const EC_R_BAD_SIGNATURE: c_int = 156;
+
 #[cfg(ossl300)]
 pub unsafe fn EVP_EC_gen(curve: *const c_char) -> *mut EVP_PKEY {
     EVP_PKEY_Q_keygen(
@@ -4,8 +4,11 @@ pub const ERR_TXT_MALLOCED: c_int = 0x01;
 pub const ERR_TXT_STRING: c_int = 0x02;
 
 pub const ERR_LIB_SYS: c_int = 2;
+pub const ERR_LIB_EVP: c_int = 6;
 pub const ERR_LIB_PEM: c_int = 9;
 pub const ERR_LIB_ASN1: c_int = 13;
+pub const ERR_LIB_CRYPTO: c_int = 15;
+pub const ERR_LIB_EC: c_int = 16;
 
 cfg_if! {
     if #[cfg(ossl300)] {
@@ -27,6 +27,12 @@ pub const EVP_CTRL_GCM_SET_IVLEN: c_int = 0x9;
 pub const EVP_CTRL_GCM_GET_TAG: c_int = 0x10;
 pub const EVP_CTRL_GCM_SET_TAG: c_int = 0x11;
 
+pub const EVP_R_BAD_DECRYPT: c_int = 100;
+pub const EVP_R_INVALID_KEY_LENGTH: c_int = 130;
+pub const EVP_R_UPDATE_ERROR: c_int = 189;
+pub const EVP_R_INVALID_IV_LENGTH: c_int = 194;
+pub const EVP_R_INVALID_LENGTH: c_int = 221;
+
 pub unsafe fn EVP_get_digestbynid(type_: c_int) -> *const EVP_MD {
     EVP_get_digestbyname(OBJ_nid2sn(type_))
 }
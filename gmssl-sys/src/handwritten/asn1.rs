@@ -89,6 +89,7 @@ extern "C" {
     pub fn ASN1_ENUMERATED_get_int64(pr: *mut i64, a: *const ASN1_ENUMERATED) -> c_int;
 
     pub fn ASN1_TYPE_free(x: *mut ASN1_TYPE);
+    pub fn i2d_ASN1_TYPE(a: *mut ASN1_TYPE, pp: *mut *mut c_uchar) -> c_int;
 }
 
 const_ptr_api! {
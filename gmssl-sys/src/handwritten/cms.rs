@@ -23,6 +23,9 @@ extern "C" {
         length: c_long,
     ) -> *mut CMS_ContentInfo;
 
+    #[cfg(ossl101)]
+    pub fn CMS_get0_type(cms: *const CMS_ContentInfo) -> *const ASN1_OBJECT;
+
     #[cfg(ossl101)]
     pub fn SMIME_read_CMS(bio: *mut BIO, bcont: *mut *mut BIO) -> *mut CMS_ContentInfo;
 
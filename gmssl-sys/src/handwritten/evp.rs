@@ -562,6 +562,7 @@ extern "C" {
 const_ptr_api! {
     extern "C" {
         pub fn EVP_PKCS82PKEY(p8: #[const_ptr_if(any(ossl110, libressl280))] PKCS8_PRIV_KEY_INFO) -> *mut EVP_PKEY;
+        pub fn EVP_PKEY2PKCS8(pkey: #[const_ptr_if(ossl300)] EVP_PKEY) -> *mut PKCS8_PRIV_KEY_INFO;
     }
 }
 
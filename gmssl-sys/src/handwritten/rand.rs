@@ -1,5 +1,17 @@
 use libc::*;
 
+#[cfg(ossl110)]
+#[repr(C)]
+pub struct RAND_METHOD {
+    pub seed: Option<unsafe extern "C" fn(buf: *const c_void, num: c_int) -> c_int>,
+    pub bytes: Option<unsafe extern "C" fn(buf: *mut c_uchar, num: c_int) -> c_int>,
+    pub cleanup: Option<unsafe extern "C" fn()>,
+    pub add:
+        Option<unsafe extern "C" fn(buf: *const c_void, num: c_int, randomness: c_double) -> c_int>,
+    pub pseudorand: Option<unsafe extern "C" fn(buf: *mut c_uchar, num: c_int) -> c_int>,
+    pub status: Option<unsafe extern "C" fn() -> c_int>,
+}
+
 extern "C" {
     pub fn RAND_bytes(buf: *mut u8, num: c_int) -> c_int;
 
@@ -9,4 +21,9 @@ extern "C" {
     pub fn RAND_status() -> c_int;
 
     pub fn RAND_add(buf: *const c_void, num: c_int, randomness: c_double);
+
+    #[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+    pub fn RAND_set_rand_method(meth: *const RAND_METHOD) -> c_int;
+    #[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+    pub fn RAND_get_rand_method() -> *const RAND_METHOD;
 }
@@ -171,6 +171,16 @@ const_ptr_api! {
     }
 }
 extern "C" {
+    pub fn X509_PUBKEY_free(a: *mut X509_PUBKEY);
+    pub fn X509_PUBKEY_set(x: *mut *mut X509_PUBKEY, pkey: *mut EVP_PKEY) -> c_int;
+    pub fn X509_PUBKEY_get0_param(
+        ppkalg: *mut *mut ASN1_OBJECT,
+        pk: *mut *const c_uchar,
+        ppklen: *mut c_int,
+        pa: *mut *mut X509_ALGOR,
+        pub_: *mut X509_PUBKEY,
+    ) -> c_int;
+
     pub fn d2i_PUBKEY(k: *mut *mut EVP_PKEY, buf: *mut *const u8, len: c_long) -> *mut EVP_PKEY;
     pub fn d2i_RSA_PUBKEY(k: *mut *mut RSA, buf: *mut *const u8, len: c_long) -> *mut RSA;
     pub fn d2i_DSA_PUBKEY(k: *mut *mut DSA, pp: *mut *const c_uchar, length: c_long) -> *mut DSA;
@@ -763,5 +773,25 @@ const_ptr_api! {
         ) -> c_int;
         pub fn i2d_X509_ATTRIBUTE(x: #[const_ptr_if(ossl300)] X509_ATTRIBUTE, buf: *mut *mut u8) -> c_int;
         pub fn X509_ATTRIBUTE_dup(x: #[const_ptr_if(ossl300)] X509_ATTRIBUTE) -> *mut X509_ATTRIBUTE;
+        pub fn i2d_PKCS8_PRIV_KEY_INFO(x: #[const_ptr_if(ossl300)] PKCS8_PRIV_KEY_INFO, buf: *mut *mut u8) -> c_int;
     }
 }
+
+extern "C" {
+    pub fn X509at_get_attr_by_NID(
+        x: *const stack_st_X509_ATTRIBUTE,
+        nid: c_int,
+        lastpos: c_int,
+    ) -> c_int;
+    pub fn X509at_get_attr(x: *const stack_st_X509_ATTRIBUTE, loc: c_int) -> *mut X509_ATTRIBUTE;
+    #[cfg(any(ossl110, libressl270))]
+    pub fn PKCS8_pkey_add1_attr_by_NID(
+        p8: *mut PKCS8_PRIV_KEY_INFO,
+        nid: c_int,
+        type_: c_int,
+        bytes: *const c_uchar,
+        len: c_int,
+    ) -> c_int;
+    #[cfg(any(ossl110, libressl270))]
+    pub fn PKCS8_pkey_get0_attrs(p8: *const PKCS8_PRIV_KEY_INFO) -> *const stack_st_X509_ATTRIBUTE;
+}
@@ -11,6 +11,12 @@ pub struct GENERAL_NAME {
 
 stack!(stack_st_GENERAL_NAME);
 
+#[repr(C)]
+pub struct OTHERNAME {
+    pub type_id: *mut ASN1_OBJECT,
+    pub value: *mut ASN1_TYPE,
+}
+
 pub const GEN_OTHERNAME: c_int = 0;
 pub const GEN_EMAIL: c_int = 1;
 pub const GEN_DNS: c_int = 2;
@@ -0,0 +1,156 @@
+//! Throughput benchmarks.
+//!
+//! Run with `cargo bench`. Each benchmark is repeated for about a second and reports the mean
+//! time and the number of heap allocations per iteration.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[allow(dead_code)]
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    f();
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let mut iters = 0;
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        iters += 1;
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+    println!(
+        "{:<40} {:>12?}/iter {:>8} allocs/iter",
+        name,
+        elapsed / iters,
+        allocations / iters as usize
+    );
+}
+
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+fn sm3_small_updates() {
+    use gmssl::hash::{Hasher, MessageDigest};
+    use gmssl::sm3::{self, Sm3};
+
+    let data = vec![0x5a; 1 << 20];
+    let expected = sm3::digest(&data).unwrap();
+
+    bench("sm3 1 MiB in 16-byte Hasher updates", || {
+        let mut h = Hasher::new(MessageDigest::sm3()).unwrap();
+        for chunk in data.chunks(16) {
+            h.update(chunk).unwrap();
+        }
+        assert_eq!(*h.finish().unwrap(), expected);
+    });
+    bench("sm3 1 MiB in 16-byte Sm3 updates", || {
+        let mut h = Sm3::new().unwrap();
+        for chunk in data.chunks(16) {
+            h.update(chunk).unwrap();
+        }
+        assert_eq!(h.finish().unwrap(), expected);
+    });
+}
+
+fn base64url_large() {
+    use gmssl::base64;
+
+    let data = (0..10_000_000).map(|i| i as u8).collect::<Vec<_>>();
+    let encoded = base64::encode_block_url(&data);
+
+    bench("base64url encode 10 MB", || {
+        assert_eq!(base64::encode_block_url(&data).len(), encoded.len());
+    });
+    bench("base64url decode 10 MB", || {
+        assert_eq!(
+            base64::decode_block_url(&encoded).unwrap().len(),
+            data.len()
+        );
+    });
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+fn sm4_gcm_key_reuse() {
+    use gmssl::aead::Sm4GcmKey;
+    use gmssl::symm;
+
+    let key = [0x42; 16];
+    let record = [0x5a; 1024];
+    let mut counter = 0u64;
+    let mut next_nonce = || {
+        counter += 1;
+        let mut nonce = [0; 12];
+        nonce[4..].copy_from_slice(&counter.to_be_bytes());
+        nonce
+    };
+
+    bench("sm4-gcm 1 KiB record, new key per call", || {
+        let nonce = next_nonce();
+        symm::sm4_gcm_encrypt(&key, &nonce, b"aad", &record).unwrap();
+    });
+
+    let mut sealing = Sm4GcmKey::new(&key).unwrap();
+    bench("sm4-gcm 1 KiB record, reused Sm4GcmKey", || {
+        let nonce = next_nonce();
+        sealing.seal(&nonce, b"aad", &record).unwrap();
+    });
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+fn sm2_encryption_reuse() {
+    use gmssl::sm2::{self, Sm2Decryptor, Sm2Encryptor, Sm2Key};
+
+    let key = Sm2Key::generate().unwrap();
+    let public_key = key.public_key().unwrap();
+    let msg = [0x5a; 64];
+    let ciphertext = sm2::encrypt(&public_key, &msg).unwrap();
+
+    bench("sm2 encrypt 64 bytes, new context per call", || {
+        sm2::encrypt(&public_key, &msg).unwrap();
+    });
+    let mut encryptor = Sm2Encryptor::new(&public_key).unwrap();
+    bench("sm2 encrypt 64 bytes, reused Sm2Encryptor", || {
+        encryptor.encrypt(&msg).unwrap();
+    });
+
+    bench("sm2 decrypt 64 bytes, new context per call", || {
+        assert_eq!(sm2::decrypt(&key, &ciphertext).unwrap(), msg);
+    });
+    let mut decryptor = Sm2Decryptor::new(&key).unwrap();
+    bench("sm2 decrypt 64 bytes, reused Sm2Decryptor", || {
+        assert_eq!(decryptor.decrypt(&ciphertext).unwrap(), msg);
+    });
+}
+
+fn main() {
+    base64url_large();
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    sm4_gcm_key_reuse();
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+    sm2_encryption_reuse();
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    sm3_small_updates();
+}
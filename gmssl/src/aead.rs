@@ -0,0 +1,414 @@
+//! Authenticated encryption with SM4.
+//!
+//! One-shot and streaming SM4-GCM are available in [`symm`](crate::symm). This module provides
+//! the remaining modes and key types for callers that encrypt many messages under one key.
+//!
+//! Requires OpenSSL 3.0.0 or newer.
+use crate::cipher::Cipher;
+use crate::cipher_ctx::CipherCtx;
+use crate::error::ErrorStack;
+use crate::symm::evp_error;
+
+/// SM4 in CCM mode, as described in [RFC 3610].
+///
+/// The nonce length `N` and the length field size `L` trade off against each other, with
+/// `N + L = 15`. A 13-byte nonce leaves a 2-byte length field, limiting messages to 64 KiB, while
+/// a 7-byte nonce allows messages of any practical size. The tag length `M` is an even number
+/// of bytes from 4 to 16.
+///
+/// The key schedule is computed once and reused for every message.
+///
+/// [RFC 3610]: https://tools.ietf.org/html/rfc3610
+pub struct Sm4Ccm {
+    ctx: CipherCtx,
+    tag_len: usize,
+}
+
+impl Sm4Ccm {
+    /// Creates a new SM4-CCM key with the given nonce and tag lengths in bytes.
+    ///
+    /// An error is returned if `nonce_len` is not between 7 and 13, or if `tag_len` is not an
+    /// even number between 4 and 16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], nonce_len: usize, tag_len: usize) -> Result<Sm4Ccm, ErrorStack> {
+        let cipher = Cipher::fetch(None, "SM4-CCM", None)?;
+        let mut ctx = CipherCtx::new()?;
+        ctx.encrypt_init(Some(&*cipher), None, None)?;
+        ctx.set_iv_length(nonce_len)?;
+        ctx.set_tag_length(tag_len)?;
+        ctx.encrypt_init(None, Some(key), None)?;
+        Ok(Sm4Ccm { ctx, tag_len })
+    }
+
+    /// Encrypts `data`, returning the ciphertext and the authentication tag.
+    ///
+    /// Additional Authenticated Data can be provided in the `aad` field. An error is returned if
+    /// `nonce` is not the length given to [`new`](Sm4Ccm::new).
+    pub fn encrypt(
+        &mut self,
+        nonce: &[u8],
+        aad: &[u8],
+        data: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), ErrorStack> {
+        self.check_nonce(nonce)?;
+
+        self.ctx.encrypt_init(None, None, Some(nonce))?;
+        self.ctx.set_data_len(data.len())?;
+        self.ctx.cipher_update(aad, None)?;
+
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        self.ctx.cipher_final_vec(&mut out)?;
+
+        let mut tag = vec![0; self.tag_len];
+        self.ctx.tag(&mut tag)?;
+        Ok((out, tag))
+    }
+
+    /// Decrypts data encrypted with [`encrypt`](Sm4Ccm::encrypt).
+    ///
+    /// An error is returned if `tag` does not match the ciphertext and `aad`, or if `nonce` or
+    /// `tag` is not the length given to [`new`](Sm4Ccm::new).
+    pub fn decrypt(
+        &mut self,
+        nonce: &[u8],
+        aad: &[u8],
+        data: &[u8],
+        tag: &[u8],
+    ) -> Result<Vec<u8>, ErrorStack> {
+        self.check_nonce(nonce)?;
+        if tag.len() != self.tag_len {
+            return Err(evp_error(ffi::EVP_R_BAD_DECRYPT));
+        }
+
+        self.ctx.decrypt_init(None, None, Some(nonce))?;
+        self.ctx.set_tag(tag)?;
+        self.ctx.set_data_len(data.len())?;
+        self.ctx.cipher_update(aad, None)?;
+
+        // CCM checks the tag as the data is processed, so there is no final step.
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        Ok(out)
+    }
+
+    fn check_nonce(&self, nonce: &[u8]) -> Result<(), ErrorStack> {
+        if nonce.len() != self.ctx.iv_length() {
+            return Err(evp_error(ffi::EVP_R_INVALID_IV_LENGTH));
+        }
+        Ok(())
+    }
+}
+
+/// An SM4-GCM key which can seal and open many messages.
+///
+/// The key schedule is computed once in [`new`](Sm4GcmKey::new), and each message only sets a
+/// new nonce on the existing context. The output matches [`sm4_gcm_encrypt`] with the same key
+/// and nonce. A nonce must never be reused with the same key.
+///
+/// [`sm4_gcm_encrypt`]: crate::symm::sm4_gcm_encrypt
+pub struct Sm4GcmKey {
+    ctx: CipherCtx,
+}
+
+impl Sm4GcmKey {
+    /// Creates a new SM4-GCM key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8]) -> Result<Sm4GcmKey, ErrorStack> {
+        let cipher = Cipher::fetch(None, "SM4-GCM", None)?;
+        let mut ctx = CipherCtx::new()?;
+        ctx.encrypt_init(Some(&*cipher), Some(key), None)?;
+        Ok(Sm4GcmKey { ctx })
+    }
+
+    /// Encrypts `data` under `nonce`, returning the ciphertext and a 16-byte authentication tag.
+    ///
+    /// Additional Authenticated Data can be provided in the `aad` field.
+    pub fn seal(
+        &mut self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        data: &[u8],
+    ) -> Result<(Vec<u8>, [u8; 16]), ErrorStack> {
+        self.ctx.encrypt_init(None, None, Some(nonce))?;
+        self.ctx.cipher_update(aad, None)?;
+
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        self.ctx.cipher_final_vec(&mut out)?;
+
+        let mut tag = [0; 16];
+        self.ctx.tag(&mut tag)?;
+        Ok((out, tag))
+    }
+
+    /// Decrypts data sealed with [`seal`](Sm4GcmKey::seal).
+    ///
+    /// The `tag` may be between 12 and 16 bytes long. If it does not match the ciphertext and
+    /// `aad`, an error is returned and none of the decrypted data is exposed.
+    pub fn open(
+        &mut self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        data: &[u8],
+        tag: &[u8],
+    ) -> Result<Vec<u8>, ErrorStack> {
+        if tag.len() < 12 || tag.len() > 16 {
+            return Err(evp_error(ffi::EVP_R_BAD_DECRYPT));
+        }
+
+        self.ctx.decrypt_init(None, None, Some(nonce))?;
+        self.ctx.cipher_update(aad, None)?;
+
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        self.ctx.set_tag(tag)?;
+        self.ctx.cipher_final_vec(&mut out)?;
+        Ok(out)
+    }
+}
+
+/// A sequence of unique nonces for an [`Sm4GcmSealingKey`] or [`Sm4GcmOpeningKey`].
+///
+/// Requires the `bound_key` feature.
+#[cfg(feature = "bound_key")]
+pub trait NonceSequence {
+    /// Returns the next nonce in the sequence, or `None` once the sequence is exhausted.
+    ///
+    /// Each nonce must be returned at most once for a given key.
+    fn advance(&mut self) -> Option<[u8; 12]>;
+}
+
+/// An SM4-GCM key bound to a [`NonceSequence`] for sealing messages.
+///
+/// Every call to [`seal_in_place`] takes the next nonce from the sequence, so a nonce cannot be
+/// reused by mistake. The peer must use an [`Sm4GcmOpeningKey`] with the same key and an
+/// identical sequence, and open messages in the order they were sealed.
+///
+/// Requires the `bound_key` feature.
+///
+/// [`seal_in_place`]: Sm4GcmSealingKey::seal_in_place
+#[cfg(feature = "bound_key")]
+pub struct Sm4GcmSealingKey<N> {
+    key: Sm4GcmKey,
+    nonces: N,
+}
+
+#[cfg(feature = "bound_key")]
+impl<N: NonceSequence> Sm4GcmSealingKey<N> {
+    /// Creates a new sealing key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], nonces: N) -> Result<Sm4GcmSealingKey<N>, ErrorStack> {
+        Ok(Sm4GcmSealingKey {
+            key: Sm4GcmKey::new(key)?,
+            nonces,
+        })
+    }
+
+    /// Encrypts `in_out` in place and appends the 16-byte authentication tag.
+    ///
+    /// An error is returned if the nonce sequence is exhausted.
+    pub fn seal_in_place(&mut self, aad: &[u8], in_out: &mut Vec<u8>) -> Result<(), ErrorStack> {
+        let nonce = next_nonce(&mut self.nonces)?;
+        let (out, tag) = self.key.seal(&nonce, aad, in_out)?;
+        in_out.copy_from_slice(&out);
+        in_out.extend_from_slice(&tag);
+        Ok(())
+    }
+}
+
+/// An SM4-GCM key bound to a [`NonceSequence`] for opening messages sealed with an
+/// [`Sm4GcmSealingKey`].
+///
+/// Requires the `bound_key` feature.
+#[cfg(feature = "bound_key")]
+pub struct Sm4GcmOpeningKey<N> {
+    key: Sm4GcmKey,
+    nonces: N,
+}
+
+#[cfg(feature = "bound_key")]
+impl<N: NonceSequence> Sm4GcmOpeningKey<N> {
+    /// Creates a new opening key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], nonces: N) -> Result<Sm4GcmOpeningKey<N>, ErrorStack> {
+        Ok(Sm4GcmOpeningKey {
+            key: Sm4GcmKey::new(key)?,
+            nonces,
+        })
+    }
+
+    /// Decrypts `in_out`, which holds ciphertext followed by a 16-byte tag, in place.
+    ///
+    /// On success the plaintext prefix of `in_out` is returned. An error is returned if the tag
+    /// does not match, if `in_out` is shorter than a tag, or if the nonce sequence is exhausted.
+    /// The nonce is consumed even if opening fails.
+    pub fn open_in_place<'a>(
+        &mut self,
+        aad: &[u8],
+        in_out: &'a mut [u8],
+    ) -> Result<&'a mut [u8], ErrorStack> {
+        let nonce = next_nonce(&mut self.nonces)?;
+        let data_len = match in_out.len().checked_sub(16) {
+            Some(data_len) => data_len,
+            None => return Err(evp_error(ffi::EVP_R_BAD_DECRYPT)),
+        };
+
+        let (data, tag) = in_out.split_at_mut(data_len);
+        let out = self.key.open(&nonce, aad, data, tag)?;
+        data.copy_from_slice(&out);
+        Ok(data)
+    }
+}
+
+#[cfg(feature = "bound_key")]
+fn next_nonce<N: NonceSequence>(nonces: &mut N) -> Result<[u8; 12], ErrorStack> {
+    nonces
+        .advance()
+        .ok_or_else(|| evp_error(ffi::EVP_R_UPDATE_ERROR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symm::sm4_gcm_encrypt;
+
+    #[test]
+    fn test_sm4_ccm() {
+        let key = [0x42; 16];
+        let nonce = [0x24; 13];
+        let aad = b"header";
+        let pt = b"The quick brown fox jumps over the lazy dog";
+
+        let mut ccm = Sm4Ccm::new(&key, 13, 16).unwrap();
+        let (ct, tag) = ccm.encrypt(&nonce, aad, pt).unwrap();
+        assert_eq!(ct.len(), pt.len());
+        assert_eq!(tag.len(), 16);
+        assert_eq!(ccm.decrypt(&nonce, aad, &ct, &tag).unwrap(), pt);
+
+        let mut bad_tag = tag.clone();
+        bad_tag[0] ^= 1;
+        assert!(ccm.decrypt(&nonce, aad, &ct, &bad_tag).is_err());
+        assert!(ccm.decrypt(&nonce, b"other", &ct, &tag).is_err());
+        assert!(ccm.decrypt(&nonce, aad, &ct, &tag[..8]).is_err());
+
+        let mut bad_ct = ct.clone();
+        bad_ct[0] ^= 1;
+        assert!(ccm.decrypt(&nonce, aad, &bad_ct, &tag).is_err());
+
+        // The key remains usable after a failed decryption.
+        assert_eq!(ccm.decrypt(&nonce, aad, &ct, &tag).unwrap(), pt);
+    }
+
+    #[test]
+    fn test_sm4_gcm_key() {
+        let key = [0x42; 16];
+        let mut sealing = Sm4GcmKey::new(&key).unwrap();
+        let mut opening = Sm4GcmKey::new(&key).unwrap();
+
+        let mut sealed = vec![];
+        for i in 0..4u8 {
+            let nonce = [i; 12];
+            let aad = [i; 5];
+            let data = vec![i; 17 * i as usize];
+
+            let (ct, tag) = sealing.seal(&nonce, &aad, &data).unwrap();
+            assert_eq!(
+                (ct.clone(), tag),
+                sm4_gcm_encrypt(&key, &nonce, &aad, &data).unwrap()
+            );
+            sealed.push((nonce, aad, data, ct, tag));
+        }
+
+        for (nonce, aad, data, ct, tag) in &sealed {
+            let mut bad_tag = *tag;
+            bad_tag[0] ^= 1;
+            assert!(opening.open(nonce, aad, ct, &bad_tag).is_err());
+            assert!(opening.open(nonce, b"other", ct, tag).is_err());
+            assert_eq!(opening.open(nonce, aad, ct, tag).unwrap(), *data);
+        }
+    }
+
+    #[cfg(feature = "bound_key")]
+    struct Counter(u64);
+
+    #[cfg(feature = "bound_key")]
+    impl NonceSequence for Counter {
+        fn advance(&mut self) -> Option<[u8; 12]> {
+            let mut nonce = [0; 12];
+            nonce[4..].copy_from_slice(&self.0.to_be_bytes());
+            self.0 = self.0.checked_add(1)?;
+            Some(nonce)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bound_key")]
+    fn test_sm4_gcm_bound_key() {
+        let key = [0x42; 16];
+        let mut sealing = Sm4GcmSealingKey::new(&key, Counter(0)).unwrap();
+        let mut opening = Sm4GcmOpeningKey::new(&key, Counter(0)).unwrap();
+
+        let mut first = b"first message".to_vec();
+        sealing.seal_in_place(b"aad", &mut first).unwrap();
+        let mut second = b"first message".to_vec();
+        sealing.seal_in_place(b"aad", &mut second).unwrap();
+
+        // The same plaintext seals differently because the nonce advanced.
+        assert_ne!(first, second);
+        let (ct, tag) = sm4_gcm_encrypt(
+            &key,
+            &Counter(1).advance().unwrap(),
+            b"aad",
+            b"first message",
+        )
+        .unwrap();
+        assert_eq!(second, [&ct[..], &tag[..]].concat());
+
+        assert_eq!(
+            opening.open_in_place(b"aad", &mut first).unwrap(),
+            b"first message"
+        );
+        assert_eq!(
+            opening.open_in_place(b"aad", &mut second).unwrap(),
+            b"first message"
+        );
+
+        // Replaying a message fails as the opening nonce has moved on.
+        let mut replay = b"first message".to_vec();
+        Sm4GcmSealingKey::new(&key, Counter(0))
+            .unwrap()
+            .seal_in_place(b"aad", &mut replay)
+            .unwrap();
+        assert!(opening.open_in_place(b"aad", &mut replay).is_err());
+    }
+
+    #[test]
+    fn test_sm4_ccm_bad_params() {
+        let key = [0x42; 16];
+        assert!(Sm4Ccm::new(&key, 14, 16).is_err());
+        assert!(Sm4Ccm::new(&key, 13, 5).is_err());
+    }
+
+    #[test]
+    fn test_sm4_ccm_bad_nonce_len() {
+        let mut ccm = Sm4Ccm::new(&[0x42; 16], 12, 16).unwrap();
+        assert!(ccm.encrypt(&[0; 13], b"", b"data").is_err());
+
+        let (ct, tag) = ccm.encrypt(&[0; 12], b"", b"data").unwrap();
+        assert!(ccm.decrypt(&[0; 7], b"", &ct, &tag).is_err());
+        assert_eq!(ccm.decrypt(&[0; 12], b"", &ct, &tag).unwrap(), b"data");
+    }
+}
@@ -226,6 +226,18 @@ impl Asn1TimeRef {
 
         Ok(Ordering::Equal)
     }
+
+    /// Returns `true` if this time is strictly earlier than `other`.
+    #[cfg(ossl102)]
+    pub fn is_before(&self, other: &Self) -> Result<bool, ErrorStack> {
+        self.compare(other).map(|o| o == Ordering::Less)
+    }
+
+    /// Returns `true` if this time is strictly later than `other`.
+    #[cfg(ossl102)]
+    pub fn is_after(&self, other: &Self) -> Result<bool, ErrorStack> {
+        self.compare(other).map(|o| o == Ordering::Greater)
+    }
 }
 
 #[cfg(ossl102)]
@@ -319,6 +331,11 @@ impl Asn1Time {
         }
     }
 
+    /// Creates a new time corresponding to the current time.
+    pub fn now() -> Result<Asn1Time, ErrorStack> {
+        Asn1Time::from_period(0)
+    }
+
     /// Creates a new time on specified interval in days from now
     pub fn days_from_now(days: u32) -> Result<Asn1Time, ErrorStack> {
         Asn1Time::from_period(days as c_long * 60 * 60 * 24)
@@ -695,6 +712,12 @@ impl fmt::Debug for Asn1ObjectRef {
     }
 }
 
+impl fmt::Debug for Asn1Object {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, fmt)
+    }
+}
+
 cfg_if! {
     if #[cfg(any(ossl110, libressl273))] {
         use ffi::ASN1_STRING_get0_data;
@@ -785,6 +808,24 @@ mod tests {
         assert!(a_ref != c_ref);
     }
 
+    #[test]
+    #[cfg(ossl102)]
+    fn time_is_before_after() {
+        let a = Asn1Time::from_str("99991231235958Z").unwrap();
+        let b = Asn1Time::from_str("99991231235959Z").unwrap();
+        assert!(a.is_before(&b).unwrap());
+        assert!(!a.is_after(&b).unwrap());
+        assert!(b.is_after(&a).unwrap());
+        assert!(!a.is_before(&a).unwrap());
+        assert!(!a.is_after(&a).unwrap());
+
+        let now = Asn1Time::now().unwrap();
+        let not_before = Asn1Time::from_str("20000101000000Z").unwrap();
+        let not_after = Asn1Time::days_from_now(1).unwrap();
+        assert!(now.is_after(&not_before).unwrap());
+        assert!(now.is_before(&not_after).unwrap());
+    }
+
     #[test]
     #[cfg(ossl102)]
     fn time_ord() {
@@ -72,6 +72,54 @@ pub fn decode_block(src: &str) -> Result<Vec<u8>, ErrorStack> {
     Ok(out)
 }
 
+/// Encodes a slice of bytes to an unpadded base64url string.
+///
+/// This uses the URL and filename safe alphabet of RFC 4648, in which `-` and `_` replace `+`
+/// and `/`, and omits the trailing `=` padding.
+///
+/// # Panics
+///
+/// Panics if the input length or computed output length overflow a signed C integer.
+pub fn encode_block_url(src: &[u8]) -> String {
+    let encoded = encode_block(src);
+    let encoded = encoded.trim_end_matches('=');
+
+    let mut out = String::with_capacity(encoded.len());
+    out.extend(encoded.chars().map(|c| match c {
+        '+' => '-',
+        '/' => '_',
+        c => c,
+    }));
+    out
+}
+
+/// Decodes a base64url-encoded string to bytes.
+///
+/// Trailing `=` padding is accepted but not required. Characters from the standard base64
+/// alphabet that are not part of the base64url alphabet (`+` and `/`) are rejected.
+///
+/// # Panics
+///
+/// Panics if the input length or computed output length overflow a signed C integer.
+pub fn decode_block_url(src: &str) -> Result<Vec<u8>, ErrorStack> {
+    let src = src.trim().trim_end_matches('=');
+
+    // Reserve room for the restored padding up front.
+    let mut translated = String::with_capacity(src.len() + 3);
+    translated.extend(src.chars().map(|c| match c {
+        '-' => '+',
+        '_' => '/',
+        // Invalid in both alphabets, so EVP_DecodeBlock reports an error.
+        '+' | '/' => '*',
+        c => c,
+    }));
+    while translated.len() % 4 != 0 {
+        translated.push('=');
+    }
+
+    decode_block(&translated)
+}
+
 fn encoded_len(src_len: LenType) -> Option<LenType> {
     let mut len = (src_len / 3).checked_mul(4)?;
 
@@ -125,4 +173,33 @@ mod tests {
         assert_eq!(b"foobar".to_vec(), decode_block(" Zm9vYmFy\n").unwrap());
         assert_eq!(b"foob".to_vec(), decode_block(" Zm9vYg==\n").unwrap());
     }
+
+    #[test]
+    fn test_encode_block_url() {
+        assert_eq!("".to_string(), encode_block_url(b""));
+        assert_eq!("-w".to_string(), encode_block_url(b"\xfb"));
+        assert_eq!("-_8".to_string(), encode_block_url(b"\xfb\xff"));
+        assert_eq!("-_-_".to_string(), encode_block_url(b"\xfb\xff\xbf"));
+    }
+
+    #[test]
+    fn test_decode_block_url() {
+        assert_eq!(b"".to_vec(), decode_block_url("").unwrap());
+        assert_eq!(b"\xfb".to_vec(), decode_block_url("-w").unwrap());
+        assert_eq!(b"\xfb".to_vec(), decode_block_url("-w==").unwrap());
+        assert_eq!(b"\xfb\xff".to_vec(), decode_block_url("-_8").unwrap());
+        assert_eq!(b"\xfb\xff".to_vec(), decode_block_url("-_8=").unwrap());
+        assert_eq!(b"\xfb\xff\xbf".to_vec(), decode_block_url("-_-_").unwrap());
+        assert!(decode_block_url("+/8").is_err());
+    }
+
+    #[test]
+    fn test_url_round_trip_large() {
+        let data = (0..1 << 20).map(|i| i as u8).collect::<Vec<_>>();
+
+        let encoded = encode_block_url(&data);
+        let expected = encode_block(&data).replace('+', "-").replace('/', "_");
+        assert_eq!(encoded, expected.trim_end_matches('='));
+        assert_eq!(decode_block_url(&encoded).unwrap(), data);
+    }
 }
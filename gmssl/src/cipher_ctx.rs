@@ -779,6 +779,38 @@ mod test {
         aes_128_cbc(cipher);
     }
 
+    #[test]
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+    fn reuse_ctx_across_sm4_modes() {
+        let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let pt = hex::decode("0123456789abcdeffedcba98765432100123456789abcdeffedcba9876543210")
+            .unwrap();
+
+        let mut ctx = CipherCtx::new().unwrap();
+
+        ctx.encrypt_init(Some(Cipher::sm4_cbc()), Some(&key), Some(&iv))
+            .unwrap();
+        ctx.set_padding(false);
+        let mut buf = vec![];
+        ctx.cipher_update_vec(&pt, &mut buf).unwrap();
+        ctx.cipher_final_vec(&mut buf).unwrap();
+        assert_eq!(
+            hex::encode(&buf),
+            "a9a268883a336315bac0c9c9ff350ab1b236a4a85616d4aabf0a83555c7d4115"
+        );
+
+        ctx.encrypt_init(Some(Cipher::sm4_ctr()), Some(&key), Some(&iv))
+            .unwrap();
+        let mut buf = vec![];
+        ctx.cipher_update_vec(&pt, &mut buf).unwrap();
+        ctx.cipher_final_vec(&mut buf).unwrap();
+        assert_eq!(
+            hex::encode(&buf),
+            "07bbd906b40da542d4514d1a97fccb7a6e24482cc90831ee244da97df7549f0a"
+        );
+    }
+
     #[test]
     fn test_stream_ciphers() {
         test_stream_cipher(Cipher::aes_192_ctr());
@@ -10,11 +10,14 @@ use foreign_types::{ForeignType, ForeignTypeRef};
 use libc::c_uint;
 use std::ptr;
 
+use crate::asn1::Asn1ObjectRef;
 use crate::bio::{MemBio, MemBioSlice};
 use crate::error::ErrorStack;
+use crate::nid::Nid;
 use crate::pkey::{HasPrivate, PKeyRef};
 use crate::stack::StackRef;
 use crate::symm::Cipher;
+use crate::util::ForeignTypeRefExt;
 use crate::x509::{store::X509StoreRef, X509Ref, X509};
 use crate::{cvt, cvt_p};
 use gmssl_macros::corresponds;
@@ -48,6 +51,42 @@ bitflags! {
     }
 }
 
+/// The content type of a CMS `ContentInfo`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CmsContentType {
+    /// `id-data`.
+    Data,
+    /// `id-signedData`.
+    SignedData,
+    /// `id-envelopedData`.
+    EnvelopedData,
+    /// `id-digestedData`.
+    DigestedData,
+    /// `id-encryptedData`.
+    EncryptedData,
+    /// `id-ct-authData`.
+    AuthenticatedData,
+    /// `id-ct-compressedData`.
+    CompressedData,
+    /// Any other content type.
+    Other(Nid),
+}
+
+impl CmsContentType {
+    fn from_nid(nid: Nid) -> CmsContentType {
+        match nid {
+            Nid::PKCS7_DATA => CmsContentType::Data,
+            Nid::PKCS7_SIGNED => CmsContentType::SignedData,
+            Nid::PKCS7_ENVELOPED => CmsContentType::EnvelopedData,
+            Nid::PKCS7_DIGEST => CmsContentType::DigestedData,
+            Nid::PKCS7_ENCRYPTED => CmsContentType::EncryptedData,
+            Nid::ID_SMIME_CT_AUTHDATA => CmsContentType::AuthenticatedData,
+            Nid::ID_SMIME_CT_COMPRESSEDDATA => CmsContentType::CompressedData,
+            nid => CmsContentType::Other(nid),
+        }
+    }
+}
+
 foreign_type_and_impl_send_sync! {
     type CType = ffi::CMS_ContentInfo;
     fn drop = ffi::CMS_ContentInfo_free;
@@ -68,6 +107,15 @@ foreign_type_and_impl_send_sync! {
 }
 
 impl CmsContentInfoRef {
+    /// Returns the content type of this `ContentInfo`.
+    #[corresponds(CMS_get0_type)]
+    pub fn content_type(&self) -> CmsContentType {
+        unsafe {
+            let obj = Asn1ObjectRef::from_const_ptr(ffi::CMS_get0_type(self.as_ptr()));
+            CmsContentType::from_nid(obj.nid())
+        }
+    }
+
     /// Given the sender's private key, `pkey` and the recipient's certificate, `cert`,
     /// decrypt the data in `self`.
     #[corresponds(CMS_decrypt)]
@@ -275,6 +323,13 @@ impl CmsContentInfo {
     }
 }
 
+/// Returns the content type of a DER-encoded CMS `ContentInfo`.
+///
+/// This allows dispatching on the kind of structure before handling its contents.
+pub fn content_type(der: &[u8]) -> Result<CmsContentType, ErrorStack> {
+    CmsContentInfo::from_der(der).map(|cms| cms.content_type())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -418,6 +473,42 @@ mod test {
         assert_eq!(data.to_vec(), out_data);
     }
 
+    #[test]
+    fn cms_content_type() {
+        let cert = X509::from_pem(include_bytes!("../test/cert.pem")).unwrap();
+        let key = PKey::private_key_from_pem(include_bytes!("../test/key.pem")).unwrap();
+
+        let signed = CmsContentInfo::sign(
+            Some(&cert),
+            Some(&key),
+            None,
+            Some(b"Hello world!"),
+            CMSOptions::empty(),
+        )
+        .unwrap();
+        assert_eq!(signed.content_type(), CmsContentType::SignedData);
+        assert_eq!(
+            content_type(&signed.to_der().unwrap()).unwrap(),
+            CmsContentType::SignedData
+        );
+
+        let mut certs = Stack::new().unwrap();
+        certs.push(cert).unwrap();
+        let enveloped = CmsContentInfo::encrypt(
+            &certs,
+            b"Hello world!",
+            Cipher::aes_128_cbc(),
+            CMSOptions::empty(),
+        )
+        .unwrap();
+        assert_eq!(
+            content_type(&enveloped.to_der().unwrap()).unwrap(),
+            CmsContentType::EnvelopedData
+        );
+
+        assert!(content_type(b"not a cms structure").is_err());
+    }
+
     #[test]
     fn cms_sign_verify_ok() {
         cms_sign_verify_generic_helper(false);
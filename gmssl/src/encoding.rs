@@ -0,0 +1,109 @@
+//! Streaming text encoding of binary data.
+//!
+//! [`encode_stream`] encodes data as it is read, so large inputs such as files can be converted to
+//! hex or base64 without holding the whole encoded result in memory.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::encoding::{encode_stream, Encoding};
+//!
+//! let mut out = vec![];
+//! encode_stream(&b"\x01\x02\x03"[..], &mut out, Encoding::Base64).unwrap();
+//! assert_eq!(out, b"AQID");
+//! ```
+use std::io::{self, Read, Write};
+
+use crate::base64;
+use crate::util::hex_encode;
+
+// A multiple of 3 so that base64 chunks join without intermediate padding.
+const CHUNK_LEN: usize = 3 * 4096;
+
+/// A text encoding supported by [`encode_stream`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lowercase hexadecimal.
+    Hex,
+    /// Standard base64 with padding and no line breaks, as produced by
+    /// [`base64::encode_block`].
+    Base64,
+}
+
+/// Reads `r` to the end, writing its contents to `w` in the given encoding.
+///
+/// The output is identical to encoding the entire input in one call.
+pub fn encode_stream<R: Read, W: Write>(mut r: R, mut w: W, encoding: Encoding) -> io::Result<()> {
+    let mut buf = vec![0; CHUNK_LEN];
+    loop {
+        let len = read_full(&mut r, &mut buf)?;
+        if len == 0 {
+            break;
+        }
+
+        let encoded = match encoding {
+            Encoding::Hex => hex_encode(&buf[..len], false),
+            Encoding::Base64 => base64::encode_block(&buf[..len]),
+        };
+        w.write_all(encoded.as_bytes())?;
+
+        if len < buf.len() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn read_full<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match r.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hands out data a few bytes at a time to exercise chunk boundaries.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(7);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_encode_stream() {
+        let data = (0..(1 << 20) + 1).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut out = vec![];
+        encode_stream(&data[..], &mut out, Encoding::Base64).unwrap();
+        assert_eq!(out, base64::encode_block(&data).as_bytes());
+
+        let mut out = vec![];
+        encode_stream(Trickle(&data), &mut out, Encoding::Base64).unwrap();
+        assert_eq!(out, base64::encode_block(&data).as_bytes());
+
+        let mut out = vec![];
+        encode_stream(Trickle(&data), &mut out, Encoding::Hex).unwrap();
+        assert_eq!(out, hex::encode(&data).as_bytes());
+    }
+
+    #[test]
+    fn test_encode_stream_empty() {
+        let mut out = vec![];
+        encode_stream(&b""[..], &mut out, Encoding::Base64).unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -16,6 +16,7 @@
 //! }
 //! ```
 use cfg_if::cfg_if;
+use gmssl_macros::corresponds;
 use libc::{c_char, c_int};
 use std::borrow::Cow;
 #[cfg(boringssl)]
@@ -339,6 +340,35 @@ impl fmt::Display for Error {
 
 impl error::Error for Error {}
 
+/// Discards any errors left on the calling thread's error queue.
+///
+/// The queue is thread-local and is only drained when an operation reports a
+/// failure, so errors ignored by other code on the same thread can otherwise
+/// be attributed to a later, unrelated call. The SSL stream methods and the
+/// hash, cipher and signature constructors call this on entry.
+#[corresponds(ERR_clear_error)]
+pub fn clear_errors() {
+    ffi::init();
+    unsafe { ffi::ERR_clear_error() }
+}
+
+/// Pushes an error with the given library and reason codes and returns the resulting stack.
+pub(crate) fn new_error(lib: c_int, reason: c_int) -> ErrorStack {
+    let file = concat!(file!(), "\0").as_ptr() as *const c_char;
+    unsafe {
+        cfg_if! {
+            if #[cfg(ossl300)] {
+                ffi::ERR_new();
+                ffi::ERR_set_debug(file, line!() as _, ptr::null());
+                ffi::ERR_set_error(lib, reason, ptr::null());
+            } else {
+                ffi::ERR_put_error(lib, 0, reason, file, line!() as _);
+            }
+        }
+    }
+    ErrorStack::get()
+}
+
 cfg_if! {
     if #[cfg(ossl300)] {
         use std::ffi::{CString};
@@ -401,6 +431,9 @@ cfg_if! {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::bn::BigNum;
+    use crate::hash::{Hasher, MessageDigest};
     #[cfg(not(ossl310))]
     use crate::nid::Nid;
 
@@ -415,4 +448,18 @@ mod tests {
         #[cfg(boringssl)]
         assert_eq!(errors[0].library_code(), ffi::ERR_LIB_OBJ as libc::c_int);
     }
+
+    #[test]
+    fn test_clear_errors() {
+        BigNum::from_dec_str("not a number").unwrap_err().put();
+        clear_errors();
+        assert!(Error::get().is_none());
+    }
+
+    #[test]
+    fn test_stale_errors_not_reported() {
+        BigNum::from_dec_str("not a number").unwrap_err().put();
+        Hasher::new(MessageDigest::sha256()).unwrap();
+        assert!(Error::get().is_none());
+    }
 }
@@ -38,8 +38,9 @@ use std::io::prelude::*;
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
-use crate::error::ErrorStack;
+use crate::error::{clear_errors, ErrorStack};
 use crate::nid::Nid;
+use crate::util::hex_encode;
 use crate::{cvt, cvt_p};
 
 cfg_if! {
@@ -244,7 +245,7 @@ unsafe impl Send for Hasher {}
 impl Hasher {
     /// Creates a new `Hasher` with the specified hash type.
     pub fn new(ty: MessageDigest) -> Result<Hasher, ErrorStack> {
-        ffi::init();
+        clear_errors();
 
         let ctx = unsafe { cvt_p(EVP_MD_CTX_new())? };
 
@@ -313,6 +314,18 @@ impl Hasher {
         }
     }
 
+    /// Returns the hash of the data written as a lowercase hex string and resets the non-XOF
+    /// hasher.
+    pub fn finish_hex(&mut self) -> Result<String, ErrorStack> {
+        self.finish().map(|digest| hex_encode(&digest, false))
+    }
+
+    /// Returns the hash of the data written as an uppercase hex string and resets the non-XOF
+    /// hasher.
+    pub fn finish_hex_upper(&mut self) -> Result<String, ErrorStack> {
+        self.finish().map(|digest| hex_encode(&digest, true))
+    }
+
     /// Writes the hash of the data into the supplied buf and resets the XOF hasher.
     /// The hash will be as long as the buf.
     #[cfg(ossl111)]
@@ -460,6 +473,9 @@ pub fn hash_xof(t: MessageDigest, data: &[u8], buf: &mut [u8]) -> Result<(), Err
     h.finish_xof(buf)
 }
 
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+pub use crate::sm3::{concat as sm3_concat, with_prefix as sm3_with_prefix};
+
 #[cfg(test)]
 mod tests {
     use hex::{self, FromHex};
@@ -765,15 +781,27 @@ mod tests {
         );
     }
 
+    // Test vectors from GM/T 0004-2012, Appendix A
     #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
-    #[test]
-    fn test_sm3() {
-        let tests = [(
+    const SM3_TESTS: [(&str, &str); 3] = [
+        (
+            "",
+            "1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b",
+        ),
+        (
             "616263",
             "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0",
-        )];
+        ),
+        (
+            "61626364616263646162636461626364616263646162636461626364616263646162636461626364616263646162636461626364616263646162636461626364",
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732",
+        ),
+    ];
 
-        for test in tests.iter() {
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    #[test]
+    fn test_sm3() {
+        for test in SM3_TESTS.iter() {
             hash_test(MessageDigest::sm3(), test);
         }
 
@@ -782,6 +810,56 @@ mod tests {
         assert_eq!(MessageDigest::sm3().type_().as_raw(), Nid::SM3.as_raw());
     }
 
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    #[test]
+    fn test_sm3_recycle() {
+        let mut h = Hasher::new(MessageDigest::sm3()).unwrap();
+        for test in SM3_TESTS.iter() {
+            hash_recycle_test(&mut h, test);
+        }
+    }
+
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    #[test]
+    fn test_sm3_clone() {
+        let (input, expected) = SM3_TESTS[2];
+        let input = Vec::from_hex(input).unwrap();
+        let p = input.len() / 2;
+
+        let mut h1 = Hasher::new(MessageDigest::sm3()).unwrap();
+        h1.write_all(&input[..p]).unwrap();
+        let mut h2 = h1.clone();
+        h2.write_all(&input[p..]).unwrap();
+        assert_eq!(hex::encode(h2.finish().unwrap()), expected);
+
+        h1.write_all(&input[p..]).unwrap();
+        assert_eq!(hex::encode(h1.finish().unwrap()), expected);
+    }
+
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    #[test]
+    fn test_sm3_finish_hex() {
+        let expected = "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0";
+
+        let mut h = Hasher::new(MessageDigest::sm3()).unwrap();
+        h.update(b"abc").unwrap();
+        assert_eq!(h.finish_hex().unwrap(), expected);
+
+        h.update(b"abc").unwrap();
+        assert_eq!(h.finish_hex_upper().unwrap(), expected.to_uppercase());
+    }
+
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    #[test]
+    fn test_sm3_concat() {
+        let expected = hash(MessageDigest::sm3(), b"abc").unwrap();
+        assert_eq!(
+            &sm3_concat(&[&b"ab"[..], &b"c"[..]]).unwrap()[..],
+            &*expected
+        );
+        assert_eq!(&sm3_with_prefix(b"a", b"bc").unwrap()[..], &*expected);
+    }
+
     #[test]
     fn from_nid() {
         assert_eq!(
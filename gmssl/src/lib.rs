@@ -134,6 +134,8 @@ mod macros;
 mod bio;
 #[macro_use]
 mod util;
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub mod aead;
 pub mod aes;
 pub mod asn1;
 pub mod base64;
@@ -148,6 +150,7 @@ pub mod dh;
 pub mod dsa;
 pub mod ec;
 pub mod ecdsa;
+pub mod encoding;
 pub mod encrypt;
 #[cfg(not(boringssl))]
 pub mod envelope;
@@ -158,12 +161,16 @@ pub mod fips;
 pub mod hash;
 #[cfg(ossl300)]
 pub mod lib_ctx;
+#[cfg(all(ossl111, not(osslconf = "OPENSSL_NO_SM4")))]
+pub mod mac;
 pub mod md;
+pub mod md5;
 pub mod md_ctx;
 pub mod memcmp;
 pub mod nid;
 #[cfg(not(any(boringssl, osslconf = "OPENSSL_NO_OCSP")))]
 pub mod ocsp;
+pub mod pem;
 pub mod pkcs12;
 #[cfg(not(boringssl))]
 pub mod pkcs5;
@@ -177,6 +184,11 @@ pub mod rand;
 pub mod rsa;
 pub mod sha;
 pub mod sign;
+pub mod signature;
+#[cfg(all(ossl111, not(osslconf = "OPENSSL_NO_SM2")))]
+pub mod sm2;
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+pub mod sm3;
 pub mod srtp;
 pub mod ssl;
 pub mod stack;
@@ -0,0 +1,207 @@
+//! Message authentication codes built on SM4.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::mac::Sm4Cmac;
+//!
+//! let key = [0x42; 16];
+//!
+//! let mut cmac = Sm4Cmac::new(&key).unwrap();
+//! cmac.update(b"some data").unwrap();
+//! let tag = cmac.finish().unwrap();
+//! assert_eq!(tag.len(), 16);
+//! ```
+use crate::error::ErrorStack;
+use crate::md_ctx::MdCtx;
+use crate::memcmp;
+use crate::pkey::PKey;
+use crate::symm::Cipher;
+#[cfg(ossl300)]
+use crate::symm::Sm4GcmEncryptor;
+
+/// An object which calculates a CMAC (OMAC1) over SM4 of some data.
+///
+/// CMAC is specified in [NIST SP 800-38B]. The key must be 16 bytes long and the tag is one SM4
+/// block.
+///
+/// [NIST SP 800-38B]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38b.pdf
+pub struct Sm4Cmac(MdCtx);
+
+impl Sm4Cmac {
+    /// Creates a new CMAC context keyed with `key`.
+    pub fn new(key: &[u8]) -> Result<Sm4Cmac, ErrorStack> {
+        let key = PKey::cmac(&Cipher::sm4_cbc(), key)?;
+        let mut ctx = MdCtx::new()?;
+        ctx.digest_sign_init(None, &key)?;
+        Ok(Sm4Cmac(ctx))
+    }
+
+    /// Feeds some data into the CMAC.
+    ///
+    /// This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), ErrorStack> {
+        self.0.digest_sign_update(data)
+    }
+
+    /// Returns the 16-byte CMAC tag of the data.
+    pub fn finish(mut self) -> Result<[u8; 16], ErrorStack> {
+        let mut out = [0; 16];
+        self.0.digest_sign_final(Some(&mut out))?;
+        Ok(out)
+    }
+
+    /// Checks the CMAC of the data against `tag` in constant time.
+    pub fn verify(self, tag: &[u8; 16]) -> Result<bool, ErrorStack> {
+        let expected = self.finish()?;
+        Ok(memcmp::eq(&expected, tag))
+    }
+}
+
+/// An object which calculates a GMAC over SM4 of some data.
+///
+/// GMAC is SM4-GCM with all of the data passed as Additional Authenticated Data and no plaintext,
+/// so the result is identical to the tag returned by [`sm4_gcm_encrypt`] with an empty plaintext.
+/// As with GCM, an IV must never be reused with the same key.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmssl::mac::Sm4Gmac;
+///
+/// let key = [0x42; 16];
+/// let iv = [0x24; 12];
+///
+/// let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+/// gmac.update(b"some header").unwrap();
+/// let tag = gmac.finish().unwrap();
+///
+/// let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+/// gmac.update(b"some header").unwrap();
+/// assert!(gmac.verify(&tag).unwrap());
+/// ```
+///
+/// [`sm4_gcm_encrypt`]: crate::symm::sm4_gcm_encrypt
+#[cfg(ossl300)]
+pub struct Sm4Gmac(Sm4GcmEncryptor);
+
+#[cfg(ossl300)]
+impl Sm4Gmac {
+    /// Creates a new GMAC context.
+    ///
+    /// The `iv` is normally 12 bytes long, but other lengths are accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Sm4Gmac, ErrorStack> {
+        Sm4GcmEncryptor::new(key, iv).map(Sm4Gmac)
+    }
+
+    /// Feeds some data into the GMAC.
+    ///
+    /// This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), ErrorStack> {
+        self.0.update_aad(data)
+    }
+
+    /// Returns the 16-byte GMAC tag of the data.
+    pub fn finish(self) -> Result<[u8; 16], ErrorStack> {
+        self.0.finish()
+    }
+
+    /// Checks the GMAC of the data against `tag` in constant time.
+    pub fn verify(self, tag: &[u8; 16]) -> Result<bool, ErrorStack> {
+        let expected = self.finish()?;
+        Ok(memcmp::eq(&expected, tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex::FromHex;
+
+    use super::*;
+    #[cfg(ossl300)]
+    use crate::symm::sm4_gcm_encrypt;
+
+    #[test]
+    fn test_sm4_cmac() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+
+        let tests = [
+            ("", "29e154322e5c7bd8ee6a25ba549b24bc"),
+            (
+                "0123456789abcdeffedcba9876543210",
+                "492950917932f9852dd47d1ba1aeb534",
+            ),
+            (
+                "0123456789abcdeffedcba98765432100123456789",
+                "d7ca5eb6836f1c1d2b150c72e3002a3f",
+            ),
+        ];
+
+        for (data, expected) in tests.iter() {
+            let data = Vec::from_hex(data).unwrap();
+            let mut cmac = Sm4Cmac::new(&key).unwrap();
+            for chunk in data.chunks(7) {
+                cmac.update(chunk).unwrap();
+            }
+            assert_eq!(hex::encode(cmac.finish().unwrap()), *expected);
+        }
+    }
+
+    #[test]
+    fn test_sm4_cmac_verify() {
+        let key = [0x42; 16];
+        let mut cmac = Sm4Cmac::new(&key).unwrap();
+        cmac.update(b"some data").unwrap();
+        let tag = cmac.finish().unwrap();
+
+        let mut cmac = Sm4Cmac::new(&key).unwrap();
+        cmac.update(b"some data").unwrap();
+        assert!(cmac.verify(&tag).unwrap());
+
+        let mut bad = tag;
+        bad[15] ^= 1;
+        let mut cmac = Sm4Cmac::new(&key).unwrap();
+        cmac.update(b"some data").unwrap();
+        assert!(!cmac.verify(&bad).unwrap());
+
+        let mut cmac = Sm4Cmac::new(&key).unwrap();
+        cmac.update(b"some dat4").unwrap();
+        assert!(!cmac.verify(&tag).unwrap());
+    }
+
+    #[test]
+    #[cfg(ossl300)]
+    fn test_sm4_gmac() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = Vec::from_hex("00001234567800000000abcd").unwrap();
+        let aad = Vec::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+
+        let (ct, expected) = sm4_gcm_encrypt(&key, &iv, &aad, &[]).unwrap();
+        assert!(ct.is_empty());
+
+        let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+        gmac.update(&aad[..9]).unwrap();
+        gmac.update(&aad[9..]).unwrap();
+        assert_eq!(gmac.finish().unwrap(), expected);
+
+        let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+        gmac.update(&aad).unwrap();
+        assert!(gmac.verify(&expected).unwrap());
+
+        let mut bad = expected;
+        bad[3] ^= 1;
+        let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+        gmac.update(&aad).unwrap();
+        assert!(!gmac.verify(&bad).unwrap());
+
+        let mut gmac = Sm4Gmac::new(&key, &iv).unwrap();
+        gmac.update(&aad[1..]).unwrap();
+        assert!(!gmac.verify(&expected).unwrap());
+    }
+}
@@ -0,0 +1,44 @@
+//! One-shot MD5 hashing.
+//!
+//! # Warning
+//!
+//! MD5 is known to be insecure - it should not be used unless required for compatibility with
+//! existing systems.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::md5;
+//!
+//! let hash = md5::hex(b"abc").unwrap();
+//! assert_eq!(hash, "900150983cd24fb0d6963f7d28e17f72");
+//! ```
+use crate::error::ErrorStack;
+use crate::hash::{hash, MessageDigest};
+use crate::util::hex_encode;
+
+/// Computes the MD5 hash of some data.
+pub fn digest(data: &[u8]) -> Result<[u8; 16], ErrorStack> {
+    let mut digest = [0; 16];
+    digest.copy_from_slice(&hash(MessageDigest::md5(), data)?);
+    Ok(digest)
+}
+
+/// Computes the MD5 hash of some data as a lowercase hex string.
+pub fn hex(data: &[u8]) -> Result<String, ErrorStack> {
+    digest(data).map(|digest| hex_encode(&digest, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            hex::encode(digest(b"abc").unwrap()),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(hex(b"").unwrap(), "d41d8cd98f00b204e9800998ecf8427e");
+    }
+}
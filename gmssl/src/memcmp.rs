@@ -10,6 +10,12 @@
 //! The utilities in this module are designed to be resistant
 //! to this type of attack.
 //!
+//! Authentication tags checked by this crate, such as AEAD tags passed to
+//! [`decrypt_aead`](crate::symm::decrypt_aead), are compared in constant time
+//! by OpenSSL itself. MACs computed with a [`Signer`](crate::sign::Signer),
+//! such as HMAC and CMAC, cannot be checked by a `Verifier` and must be
+//! compared with [`eq`].
+//!
 //! # Examples
 //!
 //! To perform a constant-time comparison of two arrays of the same length but different
@@ -1074,8 +1074,28 @@ impl Nid {
     pub const AES_128_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_128_cbc_hmac_sha1);
     pub const AES_192_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_192_cbc_hmac_sha1);
     pub const AES_256_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_256_cbc_hmac_sha1);
+    #[cfg(ossl111)]
+    pub const SM2: Nid = Nid(ffi::NID_sm2);
+    #[cfg(ossl111)]
+    pub const SM2_WITH_SM3: Nid = Nid(ffi::NID_SM2_with_SM3);
     #[cfg(any(ossl111, libressl291))]
     pub const SM3: Nid = Nid(ffi::NID_sm3);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM3WITHRSAENCRYPTION: Nid = Nid(ffi::NID_sm3WithRSAEncryption);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_ECB: Nid = Nid(ffi::NID_sm4_ecb);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_CBC: Nid = Nid(ffi::NID_sm4_cbc);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_OFB128: Nid = Nid(ffi::NID_sm4_ofb128);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_CFB128: Nid = Nid(ffi::NID_sm4_cfb128);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_CFB1: Nid = Nid(ffi::NID_sm4_cfb1);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_CFB8: Nid = Nid(ffi::NID_sm4_cfb8);
+    #[cfg(any(ossl111, libressl291))]
+    pub const SM4_CTR: Nid = Nid(ffi::NID_sm4_ctr);
     #[cfg(ossl111)]
     pub const SHA3_224: Nid = Nid(ffi::NID_sha3_224);
     #[cfg(ossl111)]
@@ -1174,4 +1194,27 @@ mod test {
             );
         }
     }
+
+    #[test]
+    #[cfg(ossl111)]
+    fn test_gm_oids() {
+        use crate::asn1::Asn1Object;
+
+        let oids = [
+            ("1.2.156.10197.1.104.1", Nid::SM4_ECB),
+            ("1.2.156.10197.1.104.2", Nid::SM4_CBC),
+            ("1.2.156.10197.1.104.3", Nid::SM4_OFB128),
+            ("1.2.156.10197.1.104.4", Nid::SM4_CFB128),
+            ("1.2.156.10197.1.104.5", Nid::SM4_CFB1),
+            ("1.2.156.10197.1.104.6", Nid::SM4_CFB8),
+            ("1.2.156.10197.1.104.7", Nid::SM4_CTR),
+            ("1.2.156.10197.1.301", Nid::SM2),
+            ("1.2.156.10197.1.401", Nid::SM3),
+            ("1.2.156.10197.1.501", Nid::SM2_WITH_SM3),
+            ("1.2.156.10197.1.504", Nid::SM3WITHRSAENCRYPTION),
+        ];
+        for (oid, nid) in &oids {
+            assert_eq!(Asn1Object::from_str(oid).unwrap().nid(), *nid, "{}", oid);
+        }
+    }
 }
@@ -0,0 +1,74 @@
+//! Writing PEM bundles.
+//!
+//! Servers commonly load their private key and certificate chain from a single file containing
+//! several concatenated PEM blocks. [`write_bundle`] produces such a file in one call.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::pem::{self, PemItem};
+//! use gmssl::x509::X509;
+//!
+//! let cert = X509::from_pem(include_bytes!("../test/cert.pem")).unwrap();
+//!
+//! let mut bundle = vec![];
+//! pem::write_bundle(&mut bundle, &[PemItem::Certificate(&cert)]).unwrap();
+//! assert_eq!(X509::stack_from_pem(&bundle).unwrap().len(), 1);
+//! ```
+use std::io::{self, Write};
+
+use crate::pkey::{PKeyRef, Private};
+use crate::x509::X509Ref;
+
+/// An object which can be written to a PEM bundle.
+#[derive(Clone, Copy)]
+pub enum PemItem<'a> {
+    /// A certificate, written as a `CERTIFICATE` block.
+    Certificate(&'a X509Ref),
+    /// A private key, written as a PKCS#8 `PRIVATE KEY` block.
+    PrivateKey(&'a PKeyRef<Private>),
+}
+
+/// Writes each item to `w` as a PEM block, in the order given.
+pub fn write_bundle<W: Write>(mut w: W, items: &[PemItem<'_>]) -> io::Result<()> {
+    for item in items {
+        match *item {
+            PemItem::Certificate(cert) => cert.write_pem(&mut w)?,
+            PemItem::PrivateKey(key) => w.write_all(&key.private_key_to_pem_pkcs8()?)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pkey::PKey;
+    use crate::x509::X509;
+
+    #[test]
+    fn test_write_bundle() {
+        let key = PKey::private_key_from_pem(include_bytes!("../test/key.pem")).unwrap();
+        let leaf = X509::from_pem(include_bytes!("../test/cert.pem")).unwrap();
+        let root = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+
+        let mut bundle = vec![];
+        write_bundle(
+            &mut bundle,
+            &[
+                PemItem::PrivateKey(&key),
+                PemItem::Certificate(&leaf),
+                PemItem::Certificate(&root),
+            ],
+        )
+        .unwrap();
+
+        let parsed = PKey::private_key_from_pem(&bundle).unwrap();
+        assert!(parsed.public_eq(&key));
+
+        let certs = X509::stack_from_pem(&bundle).unwrap();
+        assert_eq!(certs.len(), 2);
+        assert!(certs[0] == leaf);
+        assert!(certs[1] == root);
+    }
+}
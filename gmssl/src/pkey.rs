@@ -47,6 +47,7 @@ use crate::dh::Dh;
 use crate::dsa::Dsa;
 use crate::ec::EcKey;
 use crate::error::ErrorStack;
+use crate::hash::{hash, DigestBytes, MessageDigest};
 #[cfg(any(ossl110, boringssl, libressl370))]
 use crate::pkey_ctx::PkeyCtx;
 use crate::rsa::Rsa;
@@ -62,6 +63,7 @@ use std::ffi::CString;
 use std::fmt;
 use std::mem;
 use std::ptr;
+use std::slice;
 
 /// A tag type indicating that a key only has parameters.
 pub enum Params {}
@@ -238,6 +240,33 @@ where
         unsafe { ffi::EVP_PKEY_security_bits(self.as_ptr()) as u32 }
     }
 
+    /// Computes the key identifier of the public key with the digest `digest`.
+    ///
+    /// This is the hash of the `subjectPublicKey` bit string of the key's SubjectPublicKeyInfo.
+    /// With SHA-1 it is method 1 of RFC 5280 section 4.2.1.2, which is also what the
+    /// `SubjectKeyIdentifier` extension builder uses.
+    #[corresponds(X509_PUBKEY_get0_param)]
+    pub fn key_identifier(&self, digest: MessageDigest) -> Result<DigestBytes, ErrorStack> {
+        unsafe {
+            let mut pubkey = ptr::null_mut();
+            cvt(ffi::X509_PUBKEY_set(&mut pubkey, self.as_ptr()))?;
+
+            let mut pk = ptr::null();
+            let mut len = 0;
+            let r = cvt(ffi::X509_PUBKEY_get0_param(
+                ptr::null_mut(),
+                &mut pk,
+                &mut len,
+                ptr::null_mut(),
+                pubkey,
+            ))
+            .and_then(|_| hash(digest, slice::from_raw_parts(pk, len as usize)));
+
+            ffi::X509_PUBKEY_free(pubkey);
+            r
+        }
+    }
+
     /// Compares the public component of this key with another.
     #[corresponds(EVP_PKEY_cmp)]
     pub fn public_eq<U>(&self, other: &PKeyRef<U>) -> bool
@@ -11,6 +11,18 @@
 //! rand_bytes(&mut buf).unwrap();
 //! ```
 use libc::c_int;
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use libc::{c_double, c_uchar, c_void};
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use once_cell::sync::Lazy;
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use std::ptr;
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use std::slice;
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+use std::sync::RwLock;
 
 use crate::error::ErrorStack;
 use crate::{cvt, LenType};
@@ -48,6 +60,107 @@ pub fn keep_random_devices_open(keep: bool) {
     }
 }
 
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+type EntropySource = Box<dyn Fn(&mut [u8]) + Send + Sync + 'static>;
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+static ENTROPY_SOURCE: Lazy<RwLock<Option<EntropySource>>> = Lazy::new(|| RwLock::new(None));
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+static ENTROPY_METHOD: ffi::RAND_METHOD = ffi::RAND_METHOD {
+    seed: Some(entropy_seed),
+    bytes: Some(entropy_bytes),
+    cleanup: None,
+    add: Some(entropy_add),
+    pseudorand: Some(entropy_bytes),
+    status: Some(entropy_status),
+};
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+unsafe extern "C" fn entropy_bytes(buf: *mut c_uchar, num: c_int) -> c_int {
+    if num < 0 {
+        return 0;
+    }
+    let buf: &mut [u8] = if num == 0 {
+        &mut [][..]
+    } else {
+        slice::from_raw_parts_mut(buf, num as usize)
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let source = match ENTROPY_SOURCE.read() {
+            Ok(source) => source,
+            Err(_) => return false,
+        };
+        match &*source {
+            Some(f) => {
+                f(buf);
+                true
+            }
+            None => false,
+        }
+    }));
+
+    match result {
+        Ok(true) => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+unsafe extern "C" fn entropy_seed(_: *const c_void, _: c_int) -> c_int {
+    1
+}
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+unsafe extern "C" fn entropy_add(_: *const c_void, _: c_int, _: c_double) -> c_int {
+    1
+}
+
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+unsafe extern "C" fn entropy_status() -> c_int {
+    1
+}
+
+/// Installs a process-wide source of randomness.
+///
+/// Once installed, every consumer of the library's default random number generator - key
+/// generation, signature nonces, IV generation and [`rand_bytes`] itself - is served from `f`
+/// instead of the built-in CSPRNG. This is intended for deterministic simulation and fuzzing
+/// harnesses; a source installed in production code replaces the secure default for the whole
+/// process.
+///
+/// `f` must fill the entire buffer it is given. If it panics, the operation requesting
+/// randomness fails.
+///
+/// Use [`clear_entropy_source`] to restore the default generator.
+///
+/// Requires OpenSSL 1.1.0 or newer.
+#[corresponds(RAND_set_rand_method)]
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+pub fn set_entropy_source<F>(f: F) -> Result<(), ErrorStack>
+where
+    F: Fn(&mut [u8]) + Send + Sync + 'static,
+{
+    ffi::init();
+    *ENTROPY_SOURCE.write().unwrap() = Some(Box::new(f));
+    unsafe { cvt(ffi::RAND_set_rand_method(&ENTROPY_METHOD)).map(|_| ()) }
+}
+
+/// Removes a source installed by [`set_entropy_source`], restoring the default secure generator.
+///
+/// Requires OpenSSL 1.1.0 or newer.
+#[corresponds(RAND_set_rand_method)]
+#[cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+pub fn clear_entropy_source() -> Result<(), ErrorStack> {
+    ffi::init();
+    unsafe {
+        cvt(ffi::RAND_set_rand_method(ptr::null()))?;
+    }
+    *ENTROPY_SOURCE.write().unwrap() = None;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::rand_bytes;
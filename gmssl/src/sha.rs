@@ -35,6 +35,8 @@
 //! let hash = sha256(b"your data or message");
 //! println!("Hash = {}", hex::encode(hash));
 //! ```
+use crate::util::hex_encode;
+use crate::util::hex_encode;
 use cfg_if::cfg_if;
 use libc::c_void;
 use gmssl_macros::corresponds;
@@ -78,6 +80,12 @@ pub fn sha256(data: &[u8]) -> [u8; 32] {
     }
 }
 
+/// Computes the SHA256 hash of some data as a lowercase hex string.
+#[inline]
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex_encode(&sha256(data), false)
+}
+
 /// Computes the SHA384 hash of some data.
 #[corresponds(SHA384)]
 #[inline]
@@ -102,6 +110,22 @@ pub fn sha512(data: &[u8]) -> [u8; 64] {
 
 cfg_if! {
     if #[cfg(not(osslconf = "OPENSSL_NO_DEPRECATED_3_0"))] {
+        macro_rules! finish_hex {
+            () => {
+                /// Returns the hash of the data as a lowercase hex string.
+                #[inline]
+                pub fn finish_hex(self) -> String {
+                    hex_encode(&self.finish(), false)
+                }
+
+                /// Returns the hash of the data as an uppercase hex string.
+                #[inline]
+                pub fn finish_hex_upper(self) -> String {
+                    hex_encode(&self.finish(), true)
+                }
+            };
+        }
+
         /// An object which calculates a SHA1 hash of some data.
         ///
         /// # Warning
@@ -151,6 +175,8 @@ cfg_if! {
                     hash.assume_init()
                 }
             }
+
+            finish_hex!();
         }
 
         /// An object which calculates a SHA224 hash of some data.
@@ -197,6 +223,8 @@ cfg_if! {
                     hash.assume_init()
                 }
             }
+
+            finish_hex!();
         }
 
         /// An object which calculates a SHA256 hash of some data.
@@ -243,6 +271,8 @@ cfg_if! {
                     hash.assume_init()
                 }
             }
+
+            finish_hex!();
         }
 
         /// An object which calculates a SHA384 hash of some data.
@@ -289,6 +319,8 @@ cfg_if! {
                     hash.assume_init()
                 }
             }
+
+            finish_hex!();
         }
 
         /// An object which calculates a SHA512 hash of some data.
@@ -334,6 +366,8 @@ cfg_if! {
                 ffi::SHA512_Final(hash.as_mut_ptr() as *mut _, &mut self.0);
                 hash.assume_init()
             }
+
+            finish_hex!();
         }
     }
     }
@@ -402,6 +436,7 @@ mod test {
         let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
 
         assert_eq!(hex::encode(sha256(data)), expected);
+        assert_eq!(sha256_hex(data), expected);
     }
 
     #[test]
@@ -415,6 +450,17 @@ mod test {
         assert_eq!(hex::encode(hasher.finish()), expected);
     }
 
+    #[test]
+    #[cfg(not(osslconf = "OPENSSL_NO_DEPRECATED_3_0"))]
+    fn struct_256_hex() {
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        assert_eq!(hasher.clone().finish_hex(), expected);
+        assert_eq!(hasher.finish_hex_upper(), expected.to_uppercase());
+    }
+
     #[test]
     fn standalone_384() {
         let data = b"abc";
@@ -74,7 +74,7 @@ use std::io::{self, Write};
 use std::marker::PhantomData;
 use std::ptr;
 
-use crate::error::ErrorStack;
+use crate::error::{clear_errors, ErrorStack};
 use crate::hash::MessageDigest;
 use crate::pkey::{HasPrivate, HasPublic, PKeyRef};
 use crate::rsa::Padding;
@@ -168,9 +168,8 @@ impl<'a> Signer<'a> {
     where
         T: HasPrivate,
     {
+        clear_errors();
         unsafe {
-            ffi::init();
-
             let ctx = cvt_p(EVP_MD_CTX_new())?;
             let mut pctx: *mut ffi::EVP_PKEY_CTX = ptr::null_mut();
             let r = ffi::EVP_DigestSignInit(
@@ -461,9 +460,8 @@ impl<'a> Verifier<'a> {
     where
         T: HasPublic,
     {
+        clear_errors();
         unsafe {
-            ffi::init();
-
             let ctx = cvt_p(EVP_MD_CTX_new())?;
             let mut pctx: *mut ffi::EVP_PKEY_CTX = ptr::null_mut();
             let r = ffi::EVP_DigestVerifyInit(
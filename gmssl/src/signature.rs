@@ -0,0 +1,142 @@
+//! Algorithm-independent signing and verification.
+//!
+//! The [`Signer`] and [`Verifier`] traits allow code to be generic over the signature scheme. They
+//! are implemented by the SM2 signer and verifier, and by [`DigestSigner`] and
+//! [`DigestVerifier`], which cover schemes such as RSA that sign a message digest.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::hash::MessageDigest;
+//! use gmssl::pkey::PKey;
+//! use gmssl::rsa::Rsa;
+//! use gmssl::signature::{DigestSigner, DigestVerifier, Signer, Verifier};
+//!
+//! fn round_trip<S: Signer, V: Verifier>(signer: &mut S, verifier: &V) -> bool {
+//!     let signature = signer.sign(b"hello, world!").unwrap();
+//!     verifier.verify(b"hello, world!", &signature).unwrap()
+//! }
+//!
+//! let keypair = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+//! let mut signer = DigestSigner::new(MessageDigest::sha256(), &keypair);
+//! let verifier = DigestVerifier::new(MessageDigest::sha256(), &keypair);
+//! assert!(round_trip(&mut signer, &verifier));
+//! ```
+use crate::error::ErrorStack;
+use crate::hash::MessageDigest;
+use crate::pkey::{HasPrivate, HasPublic, PKey, PKeyRef};
+use crate::sign;
+
+/// A type which signs messages.
+pub trait Signer {
+    /// Signs `data`, returning the encoded signature.
+    fn sign(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorStack>;
+}
+
+/// A type which verifies signatures created by a [`Signer`].
+pub trait Verifier {
+    /// Checks the encoded `signature` of `data`.
+    ///
+    /// Returns `Ok(false)` if the signature does not match.
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, ErrorStack>;
+}
+
+/// A [`Signer`] which hashes each message with a digest before signing it with a key, as
+/// [`sign::Signer`] does.
+pub struct DigestSigner<T> {
+    digest: MessageDigest,
+    pkey: PKey<T>,
+}
+
+impl<T: HasPrivate> DigestSigner<T> {
+    /// Creates a new signer using the digest `digest` and the private key `pkey`.
+    pub fn new(digest: MessageDigest, pkey: &PKeyRef<T>) -> DigestSigner<T> {
+        DigestSigner {
+            digest,
+            pkey: pkey.to_owned(),
+        }
+    }
+}
+
+impl<T: HasPrivate> Signer for DigestSigner<T> {
+    fn sign(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        let mut signer = sign::Signer::new(self.digest, &self.pkey)?;
+        signer.update(data)?;
+        signer.sign_to_vec()
+    }
+}
+
+/// A [`Verifier`] for signatures created by a [`DigestSigner`], as [`sign::Verifier`] checks
+/// them.
+pub struct DigestVerifier<T> {
+    digest: MessageDigest,
+    pkey: PKey<T>,
+}
+
+impl<T: HasPublic> DigestVerifier<T> {
+    /// Creates a new verifier using the digest `digest` and the public key `pkey`.
+    pub fn new(digest: MessageDigest, pkey: &PKeyRef<T>) -> DigestVerifier<T> {
+        DigestVerifier {
+            digest,
+            pkey: pkey.to_owned(),
+        }
+    }
+}
+
+impl<T: HasPublic> Verifier for DigestVerifier<T> {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, ErrorStack> {
+        let mut verifier = sign::Verifier::new(self.digest, &self.pkey)?;
+        verifier.update(data)?;
+        verifier.verify(signature)
+    }
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+impl Signer for crate::sm2::Sm2Signer {
+    fn sign(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        crate::sm2::Sm2Signer::sign(self, data)
+    }
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+impl Verifier for crate::sm2::Sm2Verifier {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, ErrorStack> {
+        crate::sm2::Sm2Verifier::verify(self, data, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rsa::Rsa;
+
+    fn round_trip<S: Signer, V: Verifier>(signer: &mut S, verifier: &V) {
+        let signature = signer.sign(b"message").unwrap();
+        assert!(verifier.verify(b"message", &signature).unwrap());
+        assert!(!verifier.verify(b"messagE", &signature).unwrap());
+
+        // Each call signs only its own message.
+        let signature = signer.sign(b"message").unwrap();
+        assert!(verifier.verify(b"message", &signature).unwrap());
+    }
+
+    #[test]
+    fn rsa_round_trip() {
+        let key = Rsa::private_key_from_pem(include_bytes!("../test/rsa.pem")).unwrap();
+        let key = PKey::from_rsa(key).unwrap();
+        let mut signer = DigestSigner::new(MessageDigest::sha256(), &key);
+        let verifier = DigestVerifier::new(MessageDigest::sha256(), &key);
+        round_trip(&mut signer, &verifier);
+    }
+
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+    fn sm2_round_trip() {
+        use crate::sm2::{Sm2Key, Sm2Signer, Sm2Verifier, DEFAULT_ID};
+
+        let key = Sm2Key::generate().unwrap();
+        let mut signer = Sm2Signer::new(&key, DEFAULT_ID).unwrap();
+        let verifier = Sm2Verifier::new(&key.public_key().unwrap(), DEFAULT_ID).unwrap();
+        round_trip(&mut signer, &verifier);
+    }
+}
@@ -0,0 +1,514 @@
+//! The SM3 hash function.
+//!
+//! SM3 is the Chinese national standard cryptographic hash function, specified in GM/T 0004-2012,
+//! producing a 256-bit digest. The functions in this module cover the common one-shot case, and
+//! [`Sm3`] hashes data incrementally. For a digest chosen at runtime use [`Hasher`] with
+//! [`MessageDigest::sm3`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gmssl::sm3;
+//!
+//! let hash = sm3::hex(b"abc").unwrap();
+//! assert_eq!(hash, "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0");
+//! ```
+//!
+//! ```rust
+//! use gmssl::sm3::Sm3;
+//!
+//! let mut hasher = Sm3::new().unwrap();
+//! hasher.update(b"a").unwrap();
+//! hasher.update(b"bc").unwrap();
+//! assert_eq!(hasher.finish().unwrap(), gmssl::sm3::digest(b"abc").unwrap());
+//! ```
+//!
+//! [`Hasher`]: crate::hash::Hasher
+//! [`MessageDigest::sm3`]: crate::hash::MessageDigest::sm3
+use std::io::{self, Write};
+
+use crate::error::ErrorStack;
+use crate::hash::{Hasher, MessageDigest};
+use crate::md::Md;
+use crate::md_ctx::MdCtx;
+use crate::memcmp;
+use crate::pkey::PKey;
+use crate::util::hex_encode;
+
+/// Computes the SM3 hash of some data.
+pub fn digest(data: &[u8]) -> Result<[u8; 32], ErrorStack> {
+    concat(&[data])
+}
+
+/// Computes the SM3 hash of some data as a lowercase hex string.
+pub fn hex(data: &[u8]) -> Result<String, ErrorStack> {
+    digest(data).map(|digest| hex_encode(&digest, false))
+}
+
+/// Computes the SM3 hash of `prefix` followed by `data`.
+///
+/// This is equivalent to hashing the concatenation of the two slices without allocating a joined
+/// buffer.
+pub fn with_prefix(prefix: &[u8], data: &[u8]) -> Result<[u8; 32], ErrorStack> {
+    concat(&[prefix, data])
+}
+
+/// Computes the SM3 hash of the concatenation of `parts`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use gmssl::sm3;
+///
+/// let digest = sm3::concat(&[&b"ab"[..], &b"c"[..]])?;
+/// assert_eq!(digest, sm3::digest(b"abc")?);
+/// # Ok(()) }
+/// ```
+pub fn concat(parts: &[&[u8]]) -> Result<[u8; 32], ErrorStack> {
+    let mut h = Hasher::new(MessageDigest::sm3())?;
+    for part in parts {
+        h.update(part)?;
+    }
+    let mut digest = [0; 32];
+    digest.copy_from_slice(&h.finish()?);
+    Ok(digest)
+}
+
+/// An object which calculates an SM3 hash of some data.
+///
+/// Cloning the hasher copies its intermediate state, so a common prefix only needs to be hashed
+/// once.
+///
+/// With the `digest` feature enabled, `Sm3` implements the RustCrypto [`digest`] traits, so it can
+/// be used with generic constructions such as the `hmac` and `hkdf` crates.
+///
+/// [`digest`]: https://docs.rs/digest
+#[derive(Clone)]
+pub struct Sm3 {
+    hasher: Hasher,
+    buf: [u8; BUF_LEN],
+    buf_len: usize,
+    len: u64,
+}
+
+// Small updates are collected here and passed to the library a few blocks at a time, so that
+// many short writes don't each pay for a call into the digest.
+const BUF_LEN: usize = 4 * 64;
+
+impl Sm3 {
+    /// Creates a new hasher.
+    pub fn new() -> Result<Sm3, ErrorStack> {
+        let hasher = Hasher::new(MessageDigest::sm3())?;
+        Ok(Sm3 {
+            hasher,
+            buf: [0; BUF_LEN],
+            buf_len: 0,
+            len: 0,
+        })
+    }
+
+    /// Feeds some data into the hasher.
+    ///
+    /// This can be called multiple times. Short inputs are buffered internally, so hashing many
+    /// small pieces costs about the same as hashing them joined. The total length is tracked as a
+    /// 64-bit count, so inputs larger than 4 GiB are hashed correctly on 32-bit targets as well.
+    pub fn update(&mut self, buf: &[u8]) -> Result<(), ErrorStack> {
+        if buf.len() > BUF_LEN - self.buf_len {
+            self.flush_buf()?;
+        }
+        if buf.len() < BUF_LEN {
+            self.buf[self.buf_len..self.buf_len + buf.len()].copy_from_slice(buf);
+            self.buf_len += buf.len();
+        } else {
+            self.hasher.update(buf)?;
+        }
+        self.len += buf.len() as u64;
+        Ok(())
+    }
+
+    fn flush_buf(&mut self) -> Result<(), ErrorStack> {
+        if self.buf_len > 0 {
+            self.hasher.update(&self.buf[..self.buf_len])?;
+            self.buf_len = 0;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes fed into the hasher since it was created or last reset.
+    pub fn bytes_hashed(&self) -> u64 {
+        self.len
+    }
+
+    /// Discards any data fed into the hasher, returning it to its initial state.
+    pub fn reset(&mut self) -> Result<(), ErrorStack> {
+        *self = Sm3::new()?;
+        Ok(())
+    }
+
+    /// Returns the hash of the data.
+    pub fn finish(mut self) -> Result<[u8; 32], ErrorStack> {
+        self.flush_buf()?;
+        let bytes = self.hasher.finish()?;
+        let mut out = [0; 32];
+        out.copy_from_slice(&bytes);
+        Ok(out)
+    }
+
+    /// Returns the hash of the data as a lowercase hex string.
+    pub fn finish_hex(self) -> Result<String, ErrorStack> {
+        self.finish().map(|digest| hex_encode(&digest, false))
+    }
+
+    /// Returns the hash of the data as an uppercase hex string.
+    pub fn finish_hex_upper(self) -> Result<String, ErrorStack> {
+        self.finish().map(|digest| hex_encode(&digest, true))
+    }
+}
+
+// The RustCrypto traits cannot report errors, so these impls panic if the library fails. That
+// only happens on allocation failure or a broken provider setup.
+#[cfg(feature = "digest")]
+impl Default for Sm3 {
+    fn default() -> Sm3 {
+        Sm3::new().expect("SM3 digest failed")
+    }
+}
+
+#[cfg(feature = "digest")]
+impl digest::HashMarker for Sm3 {}
+
+#[cfg(feature = "digest")]
+impl digest::OutputSizeUser for Sm3 {
+    type OutputSize = digest::consts::U32;
+}
+
+#[cfg(feature = "digest")]
+impl digest::core_api::BlockSizeUser for Sm3 {
+    type BlockSize = digest::consts::U64;
+}
+
+#[cfg(feature = "digest")]
+impl digest::Update for Sm3 {
+    fn update(&mut self, data: &[u8]) {
+        Sm3::update(self, data).expect("SM3 digest failed");
+    }
+}
+
+#[cfg(feature = "digest")]
+impl digest::FixedOutput for Sm3 {
+    fn finalize_into(self, out: &mut digest::Output<Self>) {
+        out.copy_from_slice(&self.finish().expect("SM3 digest failed"));
+    }
+}
+
+#[cfg(feature = "digest")]
+impl digest::Reset for Sm3 {
+    fn reset(&mut self) {
+        Sm3::reset(self).expect("SM3 digest failed");
+    }
+}
+
+#[cfg(feature = "digest")]
+impl digest::FixedOutputReset for Sm3 {
+    fn finalize_into_reset(&mut self, out: &mut digest::Output<Self>) {
+        let hasher = std::mem::take(self);
+        digest::FixedOutput::finalize_into(hasher, out);
+    }
+}
+
+impl Write for Sm3 {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An object which calculates an HMAC-SM3 of some data.
+///
+/// This uses the library's HMAC implementation, so keys of any length are accepted. Keys longer
+/// than the 64-byte SM3 block size are hashed first as described in RFC 2104, and an empty key is
+/// permitted.
+pub struct Sm3Hmac(MdCtx);
+
+impl Sm3Hmac {
+    /// Creates a new HMAC-SM3 context keyed with `key`.
+    pub fn new(key: &[u8]) -> Result<Sm3Hmac, ErrorStack> {
+        let key = PKey::hmac(key)?;
+        let mut ctx = MdCtx::new()?;
+        ctx.digest_sign_init(Some(Md::sm3()), &key)?;
+        Ok(Sm3Hmac(ctx))
+    }
+
+    /// Feeds some data into the HMAC.
+    ///
+    /// This can be called multiple times.
+    pub fn update(&mut self, buf: &[u8]) -> Result<(), ErrorStack> {
+        self.0.digest_sign_update(buf)
+    }
+
+    /// Returns the HMAC of the data.
+    pub fn finish(mut self) -> Result<[u8; 32], ErrorStack> {
+        let mut out = [0; 32];
+        self.0.digest_sign_final(Some(&mut out))?;
+        Ok(out)
+    }
+
+    /// Checks the HMAC of the data against `tag` in constant time.
+    pub fn verify(self, tag: &[u8; 32]) -> Result<bool, ErrorStack> {
+        let expected = self.finish()?;
+        Ok(memcmp::eq(&expected, tag))
+    }
+}
+
+impl Write for Sm3Hmac {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            hex::encode(digest(b"").unwrap()),
+            "1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b"
+        );
+        assert_eq!(
+            hex::encode(digest(b"abc").unwrap()),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(
+            hex(b"abc").unwrap(),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+        assert_eq!(
+            hex(&b"abcd".repeat(16)).unwrap(),
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+        );
+    }
+
+    #[test]
+    fn test_concat() {
+        let a = b"domain separation prefix";
+        let b = b"message body";
+        let expected = digest(&[&a[..], &b[..]].concat()).unwrap();
+
+        assert_eq!(concat(&[&a[..], &b[..]]).unwrap(), expected);
+        assert_eq!(with_prefix(a, b).unwrap(), expected);
+        assert_eq!(concat(&[]).unwrap(), digest(b"").unwrap());
+    }
+
+    #[test]
+    fn test_sm3_empty() {
+        assert_eq!(
+            Sm3::new().unwrap().finish_hex().unwrap(),
+            "1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b"
+        );
+    }
+
+    #[test]
+    fn test_sm3_finish_hex_upper() {
+        let mut hasher = Sm3::new().unwrap();
+        hasher.update(b"abc").unwrap();
+        assert_eq!(
+            hasher.finish_hex_upper().unwrap(),
+            "66C7F0F462EEEDD9D1F2D46BDC10E4E24167C4875CF2F7A2297DA02B8F4BA8E0"
+        );
+    }
+
+    #[test]
+    fn test_sm3_incremental() {
+        let mut hasher = Sm3::new().unwrap();
+        for chunk in b"abcd".repeat(16).chunks(7) {
+            hasher.update(chunk).unwrap();
+        }
+        assert_eq!(
+            hasher.finish_hex().unwrap(),
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+        );
+    }
+
+    #[test]
+    fn test_sm3_small_updates() {
+        let data = (0..5000).map(|i| (i * 31) as u8).collect::<Vec<_>>();
+        let expected = digest(&data).unwrap();
+
+        for &size in &[1, 16, 63, 64, 255, 256, 257, 1000] {
+            let mut hasher = Sm3::new().unwrap();
+            for chunk in data.chunks(size) {
+                hasher.update(chunk).unwrap();
+            }
+            assert_eq!(hasher.finish().unwrap(), expected, "chunk size {}", size);
+        }
+
+        let mut hasher = Sm3::new().unwrap();
+        hasher.update(&data[..10]).unwrap();
+        hasher.update(&data[10..4000]).unwrap();
+        hasher.update(&data[4000..]).unwrap();
+        assert_eq!(hasher.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sm3_clone_and_reset() {
+        let mut hasher = Sm3::new().unwrap();
+        hasher.update(b"a").unwrap();
+        let mut forked = hasher.clone();
+        hasher.update(b"bc").unwrap();
+        forked.update(b"bc").unwrap();
+        assert_eq!(hasher.finish().unwrap(), forked.finish().unwrap());
+
+        let mut hasher = Sm3::new().unwrap();
+        hasher.update(b"garbage").unwrap();
+        hasher.reset().unwrap();
+        hasher.update(b"abc").unwrap();
+        assert_eq!(hasher.finish().unwrap(), digest(b"abc").unwrap());
+    }
+
+    #[test]
+    fn test_sm3_write() {
+        let mut hasher = Sm3::new().unwrap();
+        io::copy(&mut &b"abc"[..], &mut hasher).unwrap();
+        assert_eq!(hasher.finish().unwrap(), digest(b"abc").unwrap());
+    }
+
+    #[test]
+    fn test_sm3_bytes_hashed() {
+        let mut hasher = Sm3::new().unwrap();
+        assert_eq!(hasher.bytes_hashed(), 0);
+        hasher.update(b"abc").unwrap();
+        hasher.update(b"").unwrap();
+        hasher.write_all(&[0; 1000]).unwrap();
+        assert_eq!(hasher.bytes_hashed(), 1003);
+
+        let forked = hasher.clone();
+        hasher.reset().unwrap();
+        assert_eq!(hasher.bytes_hashed(), 0);
+        assert_eq!(forked.bytes_hashed(), 1003);
+    }
+
+    // Hashes just over 4 GiB, past any 32-bit length counter. Run with `--ignored`.
+    #[test]
+    #[ignore]
+    fn test_sm3_over_4gib() {
+        let chunk = vec![0; 1 << 20];
+        let mut hasher = Sm3::new().unwrap();
+        for _ in 0..4097 {
+            hasher.update(&chunk).unwrap();
+        }
+        assert_eq!(hasher.bytes_hashed(), 4097 << 20);
+        assert_eq!(
+            hasher.finish_hex().unwrap(),
+            "c0727447ec7bec76122a4b4dffefb6a28b4804f8a2e1a6f82d7dc75916c66187"
+        );
+    }
+
+    #[test]
+    fn test_sm3_hmac() {
+        let tests: [(&[u8], &[u8], &str); 4] = [
+            (
+                b"",
+                b"",
+                "0d23f72ba15e9c189a879aefc70996b06091de6e64d31b7a84004356dd915261",
+            ),
+            (
+                b"key",
+                b"The quick brown fox jumps over the lazy dog",
+                "bd4a34077888162b210645b8ebf74b9af357303789357a27c7fc457244ebd398",
+            ),
+            (
+                &hex::decode("0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20")
+                    .unwrap(),
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "be190a66f210be0df56c243d5c1a53e9301fd3f0313244a412f6ae97ab7fe407",
+            ),
+            (
+                &(0..100).collect::<Vec<u8>>(),
+                b"abc",
+                "efa0b8554e9475092d2f978d8855627a45325381b7f478f6e164faa04fd5c844",
+            ),
+        ];
+
+        for (key, data, expected) in &tests {
+            let mut hmac = Sm3Hmac::new(key).unwrap();
+            hmac.update(data).unwrap();
+            assert_eq!(hex::encode(hmac.finish().unwrap()), *expected);
+        }
+    }
+
+    #[test]
+    fn test_sm3_hmac_incremental() {
+        let mut hmac = Sm3Hmac::new(b"key").unwrap();
+        hmac.update(b"The quick brown fox ").unwrap();
+        hmac.write_all(b"jumps over the lazy dog").unwrap();
+
+        let mut expected = Sm3Hmac::new(b"key").unwrap();
+        expected
+            .update(b"The quick brown fox jumps over the lazy dog")
+            .unwrap();
+        assert_eq!(hmac.finish().unwrap(), expected.finish().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "digest")]
+    fn test_sm3_digest_traits() {
+        use hmac::{Mac, SimpleHmac};
+
+        assert_eq!(
+            <Sm3 as digest::Digest>::digest(b"abc")[..],
+            digest(b"abc").unwrap()[..]
+        );
+
+        let data = b"The quick brown fox jumps over the lazy dog";
+        for key in &[&b"key"[..], &[0x0b; 100][..]] {
+            let mut mac = <SimpleHmac<Sm3> as Mac>::new_from_slice(key).unwrap();
+            mac.update(data);
+
+            let mut expected = Sm3Hmac::new(key).unwrap();
+            expected.update(data).unwrap();
+            assert_eq!(
+                mac.finalize().into_bytes()[..],
+                expected.finish().unwrap()[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sm3_hmac_verify() {
+        let mut hmac = Sm3Hmac::new(b"key").unwrap();
+        hmac.update(b"message").unwrap();
+        let tag = hmac.finish().unwrap();
+
+        let mut hmac = Sm3Hmac::new(b"key").unwrap();
+        hmac.update(b"message").unwrap();
+        assert!(hmac.verify(&tag).unwrap());
+
+        let mut bad = tag;
+        bad[0] ^= 1;
+        let mut hmac = Sm3Hmac::new(b"key").unwrap();
+        hmac.update(b"message").unwrap();
+        assert!(!hmac.verify(&bad).unwrap());
+
+        let mut hmac = Sm3Hmac::new(b"key").unwrap();
+        hmac.update(b"messagE").unwrap();
+        assert!(!hmac.verify(&tag).unwrap());
+    }
+}
@@ -61,7 +61,7 @@ use crate::dh::{Dh, DhRef};
 #[cfg(all(ossl101, not(ossl110)))]
 use crate::ec::EcKey;
 use crate::ec::EcKeyRef;
-use crate::error::ErrorStack;
+use crate::error::{clear_errors, ErrorStack};
 use crate::ex_data::Index;
 #[cfg(ossl111)]
 use crate::hash::MessageDigest;
@@ -77,7 +77,7 @@ use crate::util::{ForeignTypeExt, ForeignTypeRefExt};
 use crate::x509::store::{X509Store, X509StoreBuilderRef, X509StoreRef};
 #[cfg(any(ossl102, libressl261))]
 use crate::x509::verify::X509VerifyParamRef;
-use crate::x509::{X509Name, X509Ref, X509StoreContextRef, X509VerifyResult, X509};
+use crate::x509::{CertBundle, X509Name, X509Ref, X509StoreContextRef, X509VerifyResult, X509};
 use crate::{cvt, cvt_n, cvt_p, init};
 use bitflags::bitflags;
 use cfg_if::cfg_if;
@@ -1014,6 +1014,19 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets the leaf certificate and certificate chain from a bundle.
+    ///
+    /// This is equivalent to calling `set_certificate` with the bundle's leaf followed by
+    /// `add_extra_chain_cert` for each certificate in its chain.
+    pub fn set_cert_bundle(&mut self, bundle: CertBundle) -> Result<(), ErrorStack> {
+        let (leaf, chain) = bundle.into_parts();
+        self.set_certificate(&leaf)?;
+        for cert in chain {
+            self.add_extra_chain_cert(cert)?;
+        }
+        Ok(())
+    }
+
     /// Loads the private key from a file.
     #[corresponds(SSL_CTX_use_PrivateKey_file)]
     pub fn set_private_key_file<P: AsRef<Path>>(
@@ -3466,6 +3479,7 @@ impl<S: Read + Write> SslStream<S> {
     #[corresponds(SSL_read_early_data)]
     #[cfg(any(ossl111, libressl340))]
     pub fn read_early_data(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        clear_errors();
         let mut read = 0;
         let ret = unsafe {
             ffi::SSL_read_early_data(
@@ -3492,6 +3506,7 @@ impl<S: Read + Write> SslStream<S> {
     #[corresponds(SSL_write_early_data)]
     #[cfg(any(ossl111, libressl340))]
     pub fn write_early_data(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        clear_errors();
         let mut written = 0;
         let ret = unsafe {
             ffi::SSL_write_early_data(
@@ -3516,6 +3531,7 @@ impl<S: Read + Write> SslStream<S> {
     /// `SslConnector` rather than `Ssl` directly, as it manages that configuration.
     #[corresponds(SSL_connect)]
     pub fn connect(&mut self) -> Result<(), Error> {
+        clear_errors();
         let ret = unsafe { ffi::SSL_connect(self.ssl.as_ptr()) };
         if ret > 0 {
             Ok(())
@@ -3532,6 +3548,7 @@ impl<S: Read + Write> SslStream<S> {
     /// `SslAcceptor` rather than `Ssl` directly, as it manages that configuration.
     #[corresponds(SSL_accept)]
     pub fn accept(&mut self) -> Result<(), Error> {
+        clear_errors();
         let ret = unsafe { ffi::SSL_accept(self.ssl.as_ptr()) };
         if ret > 0 {
             Ok(())
@@ -3545,6 +3562,7 @@ impl<S: Read + Write> SslStream<S> {
     /// This will fail if `set_accept_state` or `set_connect_state` was not called first.
     #[corresponds(SSL_do_handshake)]
     pub fn do_handshake(&mut self) -> Result<(), Error> {
+        clear_errors();
         let ret = unsafe { ffi::SSL_do_handshake(self.ssl.as_ptr()) };
         if ret > 0 {
             Ok(())
@@ -3589,6 +3607,7 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
+        clear_errors();
         let ret = self.ssl.read(buf);
         if ret > 0 {
             Ok(ret as usize)
@@ -3608,6 +3627,7 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
+        clear_errors();
         let ret = self.ssl.write(buf);
         if ret > 0 {
             Ok(ret as usize)
@@ -3624,6 +3644,7 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
+        clear_errors();
         let ret = self.ssl.peek(buf);
         if ret > 0 {
             Ok(ret as usize)
@@ -3643,6 +3664,7 @@ impl<S: Read + Write> SslStream<S> {
     /// further communication in the future.
     #[corresponds(SSL_shutdown)]
     pub fn shutdown(&mut self) -> Result<ShutdownResult, Error> {
+        clear_errors();
         match unsafe { ffi::SSL_shutdown(self.ssl.as_ptr()) } {
             0 => Ok(ShutdownResult::Sent),
             1 => Ok(ShutdownResult::Received),
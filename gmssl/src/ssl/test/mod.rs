@@ -652,6 +652,97 @@ fn add_extra_chain_cert() {
     ctx.add_extra_chain_cert(cert).unwrap();
 }
 
+#[test]
+fn set_cert_bundle() {
+    use crate::asn1::Asn1Time;
+    use crate::ec::{EcGroup, EcKey};
+    use crate::nid::Nid;
+    use crate::pkey::Private;
+    use crate::x509::extension::BasicConstraints;
+    use crate::x509::CertBundle;
+
+    fn issue(
+        cn: &str,
+        ca: bool,
+        key: &PKey<Private>,
+        issuer: Option<(&X509, &PKey<Private>)>,
+    ) -> X509 {
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder.set_pubkey(key).unwrap();
+        let (issuer_name, issuer_key) = match issuer {
+            Some((cert, cert_key)) => (cert.subject_name(), cert_key),
+            None => (&*name, key),
+        };
+        builder.set_issuer_name(issuer_name).unwrap();
+        if ca {
+            builder
+                .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                .unwrap();
+        }
+        builder.sign(issuer_key, MessageDigest::sha256()).unwrap();
+        builder.build()
+    }
+
+    fn key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    let root_key = key();
+    let root = issue("root", true, &root_key, None);
+    let intermediate_key = key();
+    let intermediate = issue(
+        "intermediate",
+        true,
+        &intermediate_key,
+        Some((&root, &root_key)),
+    );
+    let leaf_key = key();
+    let leaf = issue(
+        "leaf",
+        false,
+        &leaf_key,
+        Some((&intermediate, &intermediate_key)),
+    );
+
+    // The bundle is deliberately out of order.
+    let bundle = CertBundle::new(vec![root.clone(), leaf.clone(), intermediate.clone()]).unwrap();
+
+    let mut server = Server::builder();
+    *server.ctx() = SslContext::builder(SslMethod::tls()).unwrap();
+    server.ctx().set_cert_bundle(bundle).unwrap();
+    server.ctx().set_private_key(&leaf_key).unwrap();
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .cert_store_mut()
+        .add_cert(root.clone())
+        .unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+
+    let s = client.connect();
+    assert_eq!(s.ssl().verify_result(), X509VerifyResult::OK);
+    let chain = s.ssl().peer_cert_chain().unwrap();
+    assert_eq!(chain.len(), 3);
+    assert!(chain[0] == leaf);
+    assert!(chain[1] == intermediate);
+    assert!(chain[2] == root);
+}
+
 #[test]
 #[cfg(ossl102)]
 fn verify_valid_hostname() {
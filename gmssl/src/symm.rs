@@ -53,10 +53,18 @@
 //! ```
 use crate::cipher::CipherRef;
 use crate::cipher_ctx::{CipherCtx, CipherCtxRef};
-use crate::error::ErrorStack;
+use crate::error::{clear_errors, ErrorStack};
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+use crate::memcmp;
 use crate::nid::Nid;
 use cfg_if::cfg_if;
 use foreign_types::ForeignTypeRef;
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+use std::fs::{self, File};
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+use std::io::{self, BufWriter, Read, Write};
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+use std::path::{Path, PathBuf};
 
 #[derive(Copy, Clone)]
 pub enum Mode {
@@ -495,6 +503,7 @@ impl Crypter {
         key: &[u8],
         iv: Option<&[u8]>,
     ) -> Result<Crypter, ErrorStack> {
+        clear_errors();
         let mut ctx = CipherCtx::new()?;
 
         let f = match mode {
@@ -522,6 +531,22 @@ impl Crypter {
         Ok(Crypter { ctx })
     }
 
+    /// Creates a new `Crypter` for SM4 in GCM mode.
+    ///
+    /// SM4-GCM has no `Cipher` constant, so its implementation is fetched from the default
+    /// provider. The IV length is taken from `iv`, which is normally 12 bytes long. AAD, tags and
+    /// data are then handled as for AES GCM.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    pub fn sm4_gcm(mode: Mode, key: &[u8], iv: &[u8]) -> Result<Crypter, ErrorStack> {
+        sm4_gcm_ctx(mode, key, iv).map(|ctx| Crypter { ctx })
+    }
+
     /// Enables or disables padding.
     ///
     /// If padding is disabled, total amount of data encrypted/decrypted must
@@ -741,6 +766,9 @@ pub fn encrypt_aead(
 ///
 /// Additional Authenticated Data can be provided in the `aad` field, and the authentication tag
 /// should be provided in the `tag` field.
+///
+/// The tag is recomputed over all of the input and compared against `tag` in constant time by
+/// OpenSSL. If it does not match, an error is returned and none of the decrypted data is exposed.
 pub fn decrypt_aead(
     t: Cipher,
     key: &[u8],
@@ -774,6 +802,444 @@ pub fn decrypt_aead(
     Ok(out)
 }
 
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+const KEY_WRAP_IV: [u8; 8] = [0xa6; 8];
+
+/// Wraps a key with SM4, according to [RFC 3394](https://tools.ietf.org/html/rfc3394).
+///
+/// The key-encrypting-key `kek` must be 16 bytes long. The default initial value is used, and the
+/// returned ciphertext is 8 bytes longer than `key`.
+///
+/// An error is returned if `key` is shorter than 16 bytes or its length is not a multiple of 8.
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn sm4_key_wrap(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    if key.len() < 16 || key.len() % 8 != 0 {
+        return Err(evp_error(ffi::EVP_R_INVALID_KEY_LENGTH));
+    }
+
+    let mut c = Crypter::new(Cipher::sm4_ecb(), Mode::Encrypt, kek, None)?;
+    c.pad(false);
+
+    let n = key.len() / 8;
+    let mut out = vec![0; key.len() + 8];
+    out[..8].copy_from_slice(&KEY_WRAP_IV);
+    out[8..].copy_from_slice(key);
+
+    let mut block = [0; 16];
+    let mut buf = [0; 32];
+    for j in 0..6 {
+        for i in 1..=n {
+            block[..8].copy_from_slice(&out[..8]);
+            block[8..].copy_from_slice(&out[i * 8..(i + 1) * 8]);
+            c.update(&block, &mut buf)?;
+            xor_wrap_counter(&mut buf[..8], n * j + i);
+            out[..8].copy_from_slice(&buf[..8]);
+            out[i * 8..(i + 1) * 8].copy_from_slice(&buf[8..16]);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Unwraps a key wrapped with [`sm4_key_wrap`].
+///
+/// The integrity check value recovered from `wrapped` is compared against the default initial
+/// value in constant time. An error is returned if it does not match, or if `wrapped` is not a
+/// valid wrapped key length.
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn sm4_key_unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    if wrapped.len() < 24 || wrapped.len() % 8 != 0 {
+        return Err(evp_error(ffi::EVP_R_BAD_DECRYPT));
+    }
+
+    let mut c = Crypter::new(Cipher::sm4_ecb(), Mode::Decrypt, kek, None)?;
+    c.pad(false);
+
+    let n = wrapped.len() / 8 - 1;
+    let mut a = [0; 8];
+    a.copy_from_slice(&wrapped[..8]);
+    let mut out = wrapped[8..].to_vec();
+
+    let mut block = [0; 16];
+    let mut buf = [0; 32];
+    for j in (0..6).rev() {
+        for i in (1..=n).rev() {
+            block[..8].copy_from_slice(&a);
+            xor_wrap_counter(&mut block[..8], n * j + i);
+            block[8..].copy_from_slice(&out[(i - 1) * 8..i * 8]);
+            c.update(&block, &mut buf)?;
+            a.copy_from_slice(&buf[..8]);
+            out[(i - 1) * 8..i * 8].copy_from_slice(&buf[8..16]);
+        }
+    }
+
+    if !memcmp::eq(&a, &KEY_WRAP_IV) {
+        return Err(evp_error(ffi::EVP_R_BAD_DECRYPT));
+    }
+
+    Ok(out)
+}
+
+/// Encrypts data with SM4 in GCM mode, returning the ciphertext and a 16-byte authentication tag.
+///
+/// The `iv` is normally 12 bytes long, but other lengths are accepted. Additional Authenticated
+/// Data can be provided in the `aad` field. A truncated tag of 12 to 15 bytes is a prefix of the
+/// returned tag.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// # Panics
+///
+/// Panics if `key` is not 16 bytes long.
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn sm4_gcm_encrypt(
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    data: &[u8],
+) -> Result<(Vec<u8>, [u8; 16]), ErrorStack> {
+    let mut ctx = sm4_gcm_ctx(Mode::Encrypt, key, iv)?;
+    let mut out = vec![];
+    ctx.cipher_update(aad, None)?;
+    ctx.cipher_update_vec(data, &mut out)?;
+    ctx.cipher_final_vec(&mut out)?;
+
+    let mut tag = [0; 16];
+    ctx.tag(&mut tag)?;
+    Ok((out, tag))
+}
+
+/// Decrypts data encrypted with [`sm4_gcm_encrypt`].
+///
+/// The `tag` may be between 12 and 16 bytes long. If it does not match the ciphertext and `aad`,
+/// an error is returned and none of the decrypted data is exposed.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// # Panics
+///
+/// Panics if `key` is not 16 bytes long.
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn sm4_gcm_decrypt(
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    data: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    if tag.len() < 12 || tag.len() > 16 {
+        return Err(evp_error(ffi::EVP_R_BAD_DECRYPT));
+    }
+
+    let mut ctx = sm4_gcm_ctx(Mode::Decrypt, key, iv)?;
+    let mut out = vec![];
+    ctx.cipher_update(aad, None)?;
+    ctx.cipher_update_vec(data, &mut out)?;
+    ctx.set_tag(tag)?;
+    ctx.cipher_final_vec(&mut out)?;
+    Ok(out)
+}
+
+/// An incremental SM4-GCM encryption context.
+///
+/// Additional Authenticated Data can be supplied in pieces with [`update_aad`], followed by the
+/// plaintext in pieces with [`update`]. The result matches [`sm4_gcm_encrypt`] over the
+/// concatenated inputs.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// [`update_aad`]: Sm4GcmEncryptor::update_aad
+/// [`update`]: Sm4GcmEncryptor::update
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub struct Sm4GcmEncryptor {
+    ctx: CipherCtx,
+    data_started: bool,
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+impl Sm4GcmEncryptor {
+    /// Creates a new encryption context.
+    ///
+    /// The `iv` is normally 12 bytes long, but other lengths are accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Sm4GcmEncryptor, ErrorStack> {
+        Ok(Sm4GcmEncryptor {
+            ctx: sm4_gcm_ctx(Mode::Encrypt, key, iv)?,
+            data_started: false,
+        })
+    }
+
+    /// Feeds Additional Authenticated Data into the context.
+    ///
+    /// This can be called multiple times, but an error is returned once [`update`] has been
+    /// called.
+    ///
+    /// [`update`]: Sm4GcmEncryptor::update
+    pub fn update_aad(&mut self, aad: &[u8]) -> Result<(), ErrorStack> {
+        if self.data_started {
+            return Err(evp_error(ffi::EVP_R_UPDATE_ERROR));
+        }
+        self.ctx.cipher_update(aad, None)?;
+        Ok(())
+    }
+
+    /// Encrypts some plaintext, returning the corresponding ciphertext.
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        self.data_started = true;
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Finishes encryption, returning the 16-byte authentication tag.
+    pub fn finish(mut self) -> Result<[u8; 16], ErrorStack> {
+        let mut out = vec![];
+        self.ctx.cipher_final_vec(&mut out)?;
+        debug_assert!(out.is_empty());
+
+        let mut tag = [0; 16];
+        self.ctx.tag(&mut tag)?;
+        Ok(tag)
+    }
+}
+
+/// An incremental SM4-GCM decryption context.
+///
+/// This suits streams whose authentication tag only arrives after all of the ciphertext.
+///
+/// **Warning**: the plaintext returned by [`update`] is unauthenticated. It must not be acted
+/// upon, or released to anyone else, until [`finish`] has returned `Ok`.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// [`update`]: Sm4GcmDecryptor::update
+/// [`finish`]: Sm4GcmDecryptor::finish
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub struct Sm4GcmDecryptor {
+    ctx: CipherCtx,
+    data_started: bool,
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+impl Sm4GcmDecryptor {
+    /// Creates a new decryption context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not 16 bytes long.
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Sm4GcmDecryptor, ErrorStack> {
+        Ok(Sm4GcmDecryptor {
+            ctx: sm4_gcm_ctx(Mode::Decrypt, key, iv)?,
+            data_started: false,
+        })
+    }
+
+    /// Feeds Additional Authenticated Data into the context.
+    ///
+    /// This can be called multiple times, but an error is returned once [`update`] has been
+    /// called.
+    ///
+    /// [`update`]: Sm4GcmDecryptor::update
+    pub fn update_aad(&mut self, aad: &[u8]) -> Result<(), ErrorStack> {
+        if self.data_started {
+            return Err(evp_error(ffi::EVP_R_UPDATE_ERROR));
+        }
+        self.ctx.cipher_update(aad, None)?;
+        Ok(())
+    }
+
+    /// Decrypts some ciphertext, returning the corresponding plaintext.
+    ///
+    /// The plaintext is unauthenticated until [`finish`] succeeds.
+    ///
+    /// [`finish`]: Sm4GcmDecryptor::finish
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        self.data_started = true;
+        let mut out = vec![];
+        self.ctx.cipher_update_vec(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Checks `tag` against all of the AAD and ciphertext processed.
+    ///
+    /// An error is returned if the tag does not match, in which case all plaintext returned by
+    /// [`update`] must be discarded.
+    ///
+    /// [`update`]: Sm4GcmDecryptor::update
+    pub fn finish(mut self, tag: &[u8; 16]) -> Result<(), ErrorStack> {
+        self.ctx.set_tag(tag)?;
+        let mut out = vec![];
+        self.ctx.cipher_final_vec(&mut out)?;
+        debug_assert!(out.is_empty());
+        Ok(())
+    }
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+const FILE_IV_LEN: usize = 12;
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+const FILE_TAG_LEN: usize = 16;
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+const FILE_CHUNK_LEN: usize = 64 * 1024;
+
+/// Encrypts the file at `path_in` with SM4-GCM, writing the result to `path_out`.
+///
+/// The file is streamed in chunks, so it can be of any size. The output consists of a random
+/// 12-byte IV, the ciphertext and a 16-byte authentication tag. Additional Authenticated Data can
+/// be provided in the `aad` field.
+///
+/// The output is first written to `path_out` with `.partial` appended, and renamed into place
+/// once complete. On failure the partial file is removed.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// # Panics
+///
+/// Panics if `key` is not 16 bytes long.
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn encrypt_file<P, Q>(path_in: P, path_out: Q, key: &[u8], aad: &[u8]) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut input = File::open(path_in)?;
+
+    let mut iv = [0; FILE_IV_LEN];
+    crate::rand::rand_bytes(&mut iv)?;
+    let mut encryptor = Sm4GcmEncryptor::new(key, &iv)?;
+    encryptor.update_aad(aad)?;
+
+    write_file(path_out.as_ref(), |out| {
+        out.write_all(&iv)?;
+        let mut buf = vec![0; FILE_CHUNK_LEN];
+        loop {
+            let len = read_chunk(&mut input, &mut buf)?;
+            if len == 0 {
+                break;
+            }
+            out.write_all(&encryptor.update(&buf[..len])?)?;
+        }
+        out.write_all(&encryptor.finish()?)
+    })
+}
+
+/// Decrypts a file encrypted with [`encrypt_file`], writing the plaintext to `path_out`.
+///
+/// The authentication tag is checked after the whole file has been decrypted. The plaintext is
+/// written to `path_out` with `.partial` appended, and only renamed into place once the tag has
+/// been verified. On failure, including a tag mismatch, the partial file is removed and
+/// `path_out` is left untouched.
+///
+/// Requires OpenSSL 3.0.0 or newer.
+///
+/// # Panics
+///
+/// Panics if `key` is not 16 bytes long.
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+pub fn decrypt_file<P, Q>(path_in: P, path_out: Q, key: &[u8], aad: &[u8]) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut input = File::open(path_in)?;
+    let len = input.metadata()?.len();
+    let data_len = match len.checked_sub((FILE_IV_LEN + FILE_TAG_LEN) as u64) {
+        Some(data_len) => data_len,
+        None => return Err(evp_error(ffi::EVP_R_BAD_DECRYPT).into()),
+    };
+
+    let mut iv = [0; FILE_IV_LEN];
+    input.read_exact(&mut iv)?;
+    let mut decryptor = Sm4GcmDecryptor::new(key, &iv)?;
+    decryptor.update_aad(aad)?;
+
+    write_file(path_out.as_ref(), |out| {
+        let mut data = (&mut input).take(data_len);
+        let mut buf = vec![0; FILE_CHUNK_LEN];
+        loop {
+            let len = read_chunk(&mut data, &mut buf)?;
+            if len == 0 {
+                break;
+            }
+            out.write_all(&decryptor.update(&buf[..len])?)?;
+        }
+
+        let mut tag = [0; FILE_TAG_LEN];
+        input.read_exact(&mut tag)?;
+        decryptor.finish(&tag)?;
+        Ok(())
+    })
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+fn read_chunk<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match r.read(buf) {
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            r => return r,
+        }
+    }
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+fn write_file<F>(path: &Path, f: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let result = File::create(&partial).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        f(&mut out)?;
+        out.flush()?;
+        out.get_ref().sync_all()
+    });
+
+    match result {
+        Ok(()) => fs::rename(&partial, path),
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+fn sm4_gcm_ctx(mode: Mode, key: &[u8], iv: &[u8]) -> Result<CipherCtx, ErrorStack> {
+    clear_errors();
+    let cipher = crate::cipher::Cipher::fetch(None, "SM4-GCM", None)?;
+    let mut ctx = CipherCtx::new()?;
+
+    let f = match mode {
+        Mode::Encrypt => CipherCtxRef::encrypt_init,
+        Mode::Decrypt => CipherCtxRef::decrypt_init,
+    };
+
+    f(&mut ctx, Some(&*cipher), None, None)?;
+    ctx.set_iv_length(iv.len())?;
+    f(&mut ctx, None, Some(key), Some(iv))?;
+    Ok(ctx)
+}
+
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+fn xor_wrap_counter(a: &mut [u8], t: usize) {
+    for (a, t) in a.iter_mut().zip(&(t as u64).to_be_bytes()) {
+        *a ^= t;
+    }
+}
+
+#[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+pub(crate) fn evp_error(reason: std::os::raw::c_int) -> ErrorStack {
+    crate::error::new_error(ffi::ERR_LIB_EVP, reason)
+}
+
 cfg_if! {
     if #[cfg(any(boringssl, ossl110, libressl273))] {
         use ffi::{EVP_CIPHER_block_size, EVP_CIPHER_iv_length, EVP_CIPHER_key_length};
@@ -1304,6 +1770,35 @@ mod tests {
         assert_eq!(pt, hex::encode(out));
     }
 
+    #[test]
+    fn test_aes128_gcm_verify_fail() {
+        let key = Vec::from_hex("23dc8d23d95b6fd1251741a64f7d4f41").unwrap();
+        let iv = Vec::from_hex("f416f48ad44d9efa1179e167").unwrap();
+        let aad = b"header";
+        let pt = b"some plaintext to authenticate";
+
+        let mut tag = [0; 16];
+        let ct = encrypt_aead(Cipher::aes_128_gcm(), &key, Some(&iv), aad, pt, &mut tag).unwrap();
+
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 1;
+        let mut bad_ct = ct.clone();
+        bad_ct[0] ^= 1;
+
+        let cases: [(&[u8], &[u8], &[u8]); 3] = [
+            (aad, &bad_ct, &tag),
+            (b"other header", &ct, &tag),
+            (aad, &ct, &bad_tag),
+        ];
+        for (aad, ct, tag) in cases.iter() {
+            let out = decrypt_aead(Cipher::aes_128_gcm(), &key, Some(&iv), aad, ct, tag);
+            assert!(out.is_err());
+        }
+
+        let out = decrypt_aead(Cipher::aes_128_gcm(), &key, Some(&iv), aad, &ct, &tag).unwrap();
+        assert_eq!(out, pt);
+    }
+
     #[test]
     #[cfg(not(boringssl))]
     fn test_aes128_ccm() {
@@ -1629,4 +2124,230 @@ mod tests {
         }
         assert_eq!(ct1, &r[..count]);
     }
+
+    #[test]
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_key_wrap() {
+        let kek = Vec::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = Vec::from_hex("202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f")
+            .unwrap();
+        let expected = Vec::from_hex(
+            "6f007490cb6576aa846a7e766ccd60ab2ebef17451b3cb81eb8de41069d4a5ead40d228b331be8d8",
+        )
+        .unwrap();
+
+        let wrapped = sm4_key_wrap(&kek, &key).unwrap();
+        assert_eq!(wrapped, expected);
+        assert_eq!(sm4_key_unwrap(&kek, &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_key_unwrap_tampered() {
+        let kek = Vec::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = [0x42; 32];
+
+        let mut wrapped = sm4_key_wrap(&kek, &key).unwrap();
+        wrapped[20] ^= 1;
+        assert!(sm4_key_unwrap(&kek, &wrapped).is_err());
+
+        assert!(sm4_key_unwrap(&kek, &wrapped[..16]).is_err());
+
+        let other_kek = [0x11; 16];
+        let wrapped = sm4_key_wrap(&kek, &key).unwrap();
+        assert!(sm4_key_unwrap(&other_kek, &wrapped).is_err());
+    }
+
+    #[test]
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_key_wrap_bad_length() {
+        let kek = [0x11; 16];
+
+        assert!(sm4_key_wrap(&kek, &[0x42; 8]).is_err());
+        assert!(sm4_key_wrap(&kek, &[0x42; 20]).is_err());
+        assert!(sm4_key_wrap(&kek, &[]).is_err());
+    }
+
+    // RFC 8998 Appendix A.1
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_gcm() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = Vec::from_hex("00001234567800000000abcd").unwrap();
+        let aad = Vec::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let pt = Vec::from_hex(
+            "aaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbccccccccccccccccdddddddddddddddd\
+             eeeeeeeeeeeeeeeeffffffffffffffffeeeeeeeeeeeeeeeeaaaaaaaaaaaaaaaa",
+        )
+        .unwrap();
+        let ct = Vec::from_hex(
+            "17f399f08c67d5ee19d0dc9969c4bb7d5fd46fd3756489069157b282bb200735\
+             d82710ca5c22f0ccfa7cbf93d496ac15a56834cbcf98c397b4024a2691233b8d",
+        )
+        .unwrap();
+        let tag = Vec::from_hex("83de3541e4c2b58177e065a9bf7b62ec").unwrap();
+
+        let (out, out_tag) = sm4_gcm_encrypt(&key, &iv, &aad, &pt).unwrap();
+        assert_eq!(out, ct);
+        assert_eq!(&out_tag[..], &tag[..]);
+
+        assert_eq!(sm4_gcm_decrypt(&key, &iv, &aad, &ct, &tag).unwrap(), pt);
+        assert_eq!(
+            sm4_gcm_decrypt(&key, &iv, &aad, &ct, &tag[..12]).unwrap(),
+            pt
+        );
+
+        let mut bad_tag = tag.clone();
+        bad_tag[0] ^= 1;
+        assert!(sm4_gcm_decrypt(&key, &iv, &aad, &ct, &bad_tag).is_err());
+        assert!(sm4_gcm_decrypt(&key, &iv, &aad[1..], &ct, &tag).is_err());
+        assert!(sm4_gcm_decrypt(&key, &iv, &aad, &ct, &tag[..8]).is_err());
+
+        let mut bad_ct = ct.clone();
+        bad_ct[10] ^= 1;
+        assert!(sm4_gcm_decrypt(&key, &iv, &aad, &bad_ct, &tag).is_err());
+    }
+
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_gcm_crypter() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = Vec::from_hex("00001234567800000000abcd").unwrap();
+        let aad = Vec::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let pt = [0x5a; 100];
+        let (ct, tag) = sm4_gcm_encrypt(&key, &iv, &aad, &pt).unwrap();
+
+        let mut c = Crypter::sm4_gcm(Mode::Encrypt, &key, &iv).unwrap();
+        c.aad_update(&aad[..7]).unwrap();
+        c.aad_update(&aad[7..]).unwrap();
+        let mut out = vec![0; pt.len() + 16];
+        let mut count = c.update(&pt[..33], &mut out).unwrap();
+        count += c.update(&pt[33..], &mut out[count..]).unwrap();
+        count += c.finalize(&mut out[count..]).unwrap();
+        out.truncate(count);
+        let mut out_tag = [0; 16];
+        c.get_tag(&mut out_tag).unwrap();
+        assert_eq!(out, ct);
+        assert_eq!(out_tag, tag);
+
+        let mut c = Crypter::sm4_gcm(Mode::Decrypt, &key, &iv).unwrap();
+        c.aad_update(&aad).unwrap();
+        let mut out = vec![0; ct.len() + 16];
+        let mut count = c.update(&ct, &mut out).unwrap();
+        c.set_tag(&tag).unwrap();
+        count += c.finalize(&mut out[count..]).unwrap();
+        assert_eq!(&out[..count], &pt[..]);
+
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 1;
+        let mut c = Crypter::sm4_gcm(Mode::Decrypt, &key, &iv).unwrap();
+        c.aad_update(&aad).unwrap();
+        let count = c.update(&ct, &mut out).unwrap();
+        c.set_tag(&bad_tag).unwrap();
+        assert!(c.finalize(&mut out[count..]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_gcm_encryptor() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = Vec::from_hex("00001234567800000000abcd").unwrap();
+        let aad = Vec::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let pt = (0..100).collect::<Vec<u8>>();
+        let (ct, tag) = sm4_gcm_encrypt(&key, &iv, &aad, &pt).unwrap();
+
+        let mut e = Sm4GcmEncryptor::new(&key, &iv).unwrap();
+        for chunk in aad.chunks(3) {
+            e.update_aad(chunk).unwrap();
+        }
+        let mut out = vec![];
+        for chunk in pt.chunks(7) {
+            out.extend(e.update(chunk).unwrap());
+        }
+        assert!(e.update_aad(b"late").is_err());
+        assert_eq!(out, ct);
+        assert_eq!(e.finish().unwrap(), tag);
+    }
+
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_gcm_decryptor() {
+        let key = Vec::from_hex("0123456789abcdeffedcba9876543210").unwrap();
+        let iv = Vec::from_hex("00001234567800000000abcd").unwrap();
+        let aad = Vec::from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let pt = (0..100).collect::<Vec<u8>>();
+        let (ct, tag) = sm4_gcm_encrypt(&key, &iv, &aad, &pt).unwrap();
+
+        let mut d = Sm4GcmDecryptor::new(&key, &iv).unwrap();
+        d.update_aad(&aad[..5]).unwrap();
+        d.update_aad(&aad[5..]).unwrap();
+        let mut out = vec![];
+        for chunk in ct.chunks(7) {
+            out.extend(d.update(chunk).unwrap());
+        }
+        d.finish(&tag).unwrap();
+        assert_eq!(out, pt);
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 1;
+        let mut d = Sm4GcmDecryptor::new(&key, &iv).unwrap();
+        d.update_aad(&aad).unwrap();
+        d.update(&ct).unwrap();
+        assert!(d.finish(&bad_tag).is_err());
+
+        let mut bad_aad = aad.clone();
+        bad_aad[0] ^= 1;
+        let mut d = Sm4GcmDecryptor::new(&key, &iv).unwrap();
+        d.update_aad(&bad_aad).unwrap();
+        d.update(&ct).unwrap();
+        assert!(d.finish(&tag).is_err());
+
+        let mut bad_ct = ct;
+        bad_ct[0] ^= 1;
+        let mut d = Sm4GcmDecryptor::new(&key, &iv).unwrap();
+        d.update_aad(&aad).unwrap();
+        d.update(&bad_ct).unwrap();
+        assert!(d.finish(&tag).is_err());
+    }
+
+    #[test]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM4")))]
+    fn test_sm4_gcm_file() {
+        let dir = std::env::temp_dir();
+        let name = format!("gmssl-sm4-gcm-{}", std::process::id());
+        let plain = dir.join(format!("{}.txt", name));
+        let sealed = dir.join(format!("{}.enc", name));
+        let opened = dir.join(format!("{}.out", name));
+
+        let key = [0x42; 16];
+        let data = (0..3 * 1024 * 1024 + 5)
+            .map(|i| (i * 31) as u8)
+            .collect::<Vec<_>>();
+        fs::write(&plain, &data).unwrap();
+
+        encrypt_file(&plain, &sealed, &key, b"aad").unwrap();
+        assert_eq!(
+            fs::metadata(&sealed).unwrap().len() as usize,
+            data.len() + FILE_IV_LEN + FILE_TAG_LEN
+        );
+        decrypt_file(&sealed, &opened, &key, b"aad").unwrap();
+        assert!(fs::read(&opened).unwrap() == data);
+        fs::remove_file(&opened).unwrap();
+
+        assert!(decrypt_file(&sealed, &opened, &key, b"other").is_err());
+        assert!(!opened.exists());
+
+        let mut tampered = fs::read(&sealed).unwrap();
+        tampered[data.len() / 2] ^= 1;
+        fs::write(&sealed, &tampered).unwrap();
+        assert!(decrypt_file(&sealed, &opened, &key, b"aad").is_err());
+        assert!(!opened.exists());
+
+        fs::write(&sealed, &tampered[..FILE_IV_LEN]).unwrap();
+        assert!(decrypt_file(&sealed, &opened, &key, b"aad").is_err());
+        assert!(!opened.exists());
+
+        fs::remove_file(&plain).unwrap();
+        fs::remove_file(&sealed).unwrap();
+    }
 }
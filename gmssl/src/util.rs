@@ -1,7 +1,8 @@
-use crate::error::ErrorStack;
+use crate::error::{new_error, ErrorStack};
 use foreign_types::{ForeignType, ForeignTypeRef};
 use libc::{c_char, c_int, c_void};
 use std::any::Any;
+use std::mem;
 use std::panic::{self, AssertUnwindSafe};
 use std::slice;
 
@@ -91,3 +92,61 @@ pub trait ForeignTypeRefExt: ForeignTypeRef {
     }
 }
 impl<FT: ForeignTypeRef> ForeignTypeRefExt for FT {}
+
+/// Decodes a hex string of either case into bytes.
+pub fn hex_decode(s: &str) -> Result<Vec<u8>, ErrorStack> {
+    if s.len() % 2 != 0 {
+        return Err(new_error(
+            ffi::ERR_LIB_CRYPTO,
+            ffi::CRYPTO_R_ODD_NUMBER_OF_DIGITS,
+        ));
+    }
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let digit = |c: u8| (c as char).to_digit(16);
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(hi), Some(lo)) => Ok((hi << 4 | lo) as u8),
+                _ => Err(new_error(
+                    ffi::ERR_LIB_CRYPTO,
+                    ffi::CRYPTO_R_ILLEGAL_HEX_DIGIT,
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Encodes `bytes` as a hex string, using uppercase digits if `upper` is set.
+pub fn hex_encode(bytes: &[u8], upper: bool) -> String {
+    let digits = if upper {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        s.push(digits[(b >> 4) as usize] as char);
+        s.push(digits[(b & 0xf) as usize] as char);
+    }
+    s
+}
+
+/// Returns the header and content lengths of the DER element at the start of `der`.
+pub(crate) fn der_header(der: &[u8]) -> Option<(usize, usize)> {
+    let first = *der.get(1)?;
+    let (header, len) = if first & 0x80 == 0 {
+        (2, first as usize)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > mem::size_of::<usize>() {
+            return None;
+        }
+        let bytes = der.get(2..2 + n)?;
+        let len = bytes.iter().fold(0, |len, &b| (len << 8) | b as usize);
+        (2 + n, len)
+    };
+    if der.len() - header < len {
+        return None;
+    }
+    Some((header, len))
+}
@@ -100,6 +100,44 @@ pub fn dir() -> &'static str {
     }
 }
 
+/// A summary of the linked library's build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The text variant of the version number, as returned by [`version`].
+    pub version: &'static str,
+
+    /// The date of the build process, as returned by [`built_on`].
+    pub built_on: &'static str,
+
+    /// The "Configure" target of the build, as returned by [`platform`].
+    pub platform: &'static str,
+
+    /// Whether the SM3 digest is available.
+    pub sm3: bool,
+
+    /// Whether the SM4 cipher is available.
+    pub sm4: bool,
+}
+
+/// Returns a summary of the linked library's build.
+///
+/// The feature flags reflect the configuration detected when this crate was compiled.
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: version(),
+        built_on: built_on(),
+        platform: platform(),
+        sm3: cfg!(all(
+            any(ossl111, libressl291),
+            not(osslconf = "OPENSSL_NO_SM3")
+        )),
+        sm4: cfg!(all(
+            any(ossl111, libressl291),
+            not(osslconf = "OPENSSL_NO_SM4")
+        )),
+    }
+}
+
 /// This test ensures that we do not segfault when calling the functions of this module
 /// and that the strings respect a reasonable format.
 #[test]
@@ -133,3 +171,17 @@ fn test_versions() {
     }
     assert!(dir().starts_with("OPENSSLDIR:"));
 }
+
+#[test]
+fn test_build_info() {
+    let info = build_info();
+    assert!(!info.version.is_empty());
+    assert_eq!(info.version, version());
+    assert_eq!(info.built_on, built_on());
+    assert_eq!(info.platform, platform());
+
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM3")))]
+    assert!(info.sm3);
+    #[cfg(all(any(ossl111, libressl291), not(osslconf = "OPENSSL_NO_SM4")))]
+    assert!(info.sm4);
+}
@@ -15,6 +15,7 @@ use std::convert::{TryFrom, TryInto};
 use std::error::Error;
 use std::ffi::{CStr, CString};
 use std::fmt;
+use std::io::{self, Write};
 use std::marker::PhantomData;
 use std::mem;
 use std::net::IpAddr;
@@ -27,17 +28,18 @@ use crate::asn1::{
     Asn1BitStringRef, Asn1Enumerated, Asn1IntegerRef, Asn1Object, Asn1ObjectRef, Asn1StringRef,
     Asn1TimeRef, Asn1Type,
 };
-use crate::bio::MemBioSlice;
+use crate::bio::{MemBio, MemBioSlice};
 use crate::conf::ConfRef;
-use crate::error::ErrorStack;
+use crate::error::{new_error, ErrorStack};
 use crate::ex_data::Index;
 use crate::hash::{DigestBytes, MessageDigest};
 use crate::nid::Nid;
 use crate::pkey::{HasPrivate, HasPublic, PKey, PKeyRef, Public};
+use crate::sign::Verifier;
 use crate::ssl::SslRef;
 use crate::stack::{Stack, StackRef, Stackable};
 use crate::string::OpensslString;
-use crate::util::{ForeignTypeExt, ForeignTypeRefExt};
+use crate::util::{der_header, ForeignTypeExt, ForeignTypeRefExt};
 use crate::{cvt, cvt_n, cvt_p};
 use gmssl_macros::corresponds;
 
@@ -439,6 +441,21 @@ impl X509Ref {
         }
     }
 
+    /// Returns this certificate's subject alternative names as typed entries.
+    ///
+    /// Entries of kinds not represented by [`SubjectAltName`], such as
+    /// `directoryName`, are skipped. A certificate without the extension yields
+    /// an empty vector.
+    pub fn typed_subject_alt_names(&self) -> Vec<SubjectAltName> {
+        match self.subject_alt_names() {
+            Some(names) => names
+                .iter()
+                .filter_map(|n| n.to_subject_alt_name())
+                .collect(),
+            None => vec![],
+        }
+    }
+
     /// Returns this certificate's CRL distribution points, if they exist.
     #[corresponds(X509_get_ext_d2i)]
     pub fn crl_distribution_points(&self) -> Option<Stack<DistPoint>> {
@@ -491,6 +508,18 @@ impl X509Ref {
         }
     }
 
+    /// Returns the certificate's SM2 public key as an uncompressed point, `04 || x || y`.
+    ///
+    /// This is the form in which TLCP encryption certificates are commonly cached. An error is
+    /// returned if the certificate's key is not on the SM2 curve.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(all(ossl111, not(osslconf = "OPENSSL_NO_SM2")))]
+    pub fn sm2_public_key_point(&self) -> Result<[u8; 65], ErrorStack> {
+        let spki = self.public_key()?.public_key_to_der()?;
+        crate::sm2::Sm2PublicKey::from_spki_der(&spki)?.to_bytes()
+    }
+
     /// Returns a digest of the DER representation of the certificate.
     #[corresponds(X509_digest)]
     pub fn digest(&self, hash_type: MessageDigest) -> Result<DigestBytes, ErrorStack> {
@@ -556,6 +585,27 @@ impl X509Ref {
         }
     }
 
+    /// Returns the certificate's signature algorithm as a [`SignatureAlgorithm`].
+    ///
+    /// Algorithms without a dedicated variant are returned as [`SignatureAlgorithm::Other`].
+    pub fn signature_algorithm_kind(&self) -> SignatureAlgorithm<'_> {
+        let object = self.signature_algorithm().object();
+        match object.nid() {
+            #[cfg(ossl111)]
+            Nid::SM2_WITH_SM3 => SignatureAlgorithm::Sm2WithSm3,
+            #[cfg(any(ossl111, libressl291))]
+            Nid::SM3WITHRSAENCRYPTION => SignatureAlgorithm::RsaWithSm3,
+            Nid::SHA1WITHRSAENCRYPTION => SignatureAlgorithm::RsaWithSha1,
+            Nid::SHA256WITHRSAENCRYPTION => SignatureAlgorithm::RsaWithSha256,
+            Nid::SHA384WITHRSAENCRYPTION => SignatureAlgorithm::RsaWithSha384,
+            Nid::SHA512WITHRSAENCRYPTION => SignatureAlgorithm::RsaWithSha512,
+            Nid::ECDSA_WITH_SHA256 => SignatureAlgorithm::EcdsaWithSha256,
+            Nid::ECDSA_WITH_SHA384 => SignatureAlgorithm::EcdsaWithSha384,
+            Nid::ECDSA_WITH_SHA512 => SignatureAlgorithm::EcdsaWithSha512,
+            _ => SignatureAlgorithm::Other(object),
+        }
+    }
+
     /// Returns the list of OCSP responder URLs specified in the certificate's Authority Information
     /// Access field.
     #[corresponds(X509_get1_ocsp)]
@@ -572,6 +622,74 @@ impl X509Ref {
         }
     }
 
+    /// Returns `true` if the certificate's basic constraints mark it as a CA.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get_extension_flags)]
+    #[cfg(ossl110)]
+    pub fn is_ca(&self) -> bool {
+        unsafe { ffi::X509_get_extension_flags(self.as_ptr()) & ffi::EXFLAG_CA != 0 }
+    }
+
+    /// Returns the path length constraint from the certificate's basic constraints.
+    ///
+    /// Returns `None` if the certificate is not a CA or does not limit the path length.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get_pathlen)]
+    #[cfg(ossl110)]
+    pub fn path_len_constraint(&self) -> Option<u32> {
+        if !self.is_ca() {
+            return None;
+        }
+        let pathlen = unsafe { ffi::X509_get_pathlen(self.as_ptr()) };
+        u32::try_from(pathlen).ok()
+    }
+
+    /// Returns the contents of the certificate's subject key identifier extension, if present.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get0_subject_key_id)]
+    #[cfg(ossl110)]
+    pub fn subject_key_id(&self) -> Option<&[u8]> {
+        unsafe {
+            let id = ffi::X509_get0_subject_key_id(self.as_ptr());
+            if id.is_null() {
+                return None;
+            }
+            let ptr = ASN1_STRING_get0_data(id as *mut _);
+            let len = ffi::ASN1_STRING_length(id as *mut _);
+            Some(slice::from_raw_parts(ptr, len as usize))
+        }
+    }
+
+    /// Returns `true` if the certificate issued itself and its signature verifies
+    /// with its own public key.
+    pub fn is_self_signed(&self) -> bool {
+        if self.issued(self) != X509VerifyResult::OK {
+            return false;
+        }
+        match self.public_key() {
+            Ok(key) => self.verify(&key).unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if this certificate has the same subject name and public key as `other`.
+    ///
+    /// Validity period, serial number, issuer and extensions are not compared, so a
+    /// certificate reissued for the same key is considered a renewal of its predecessor.
+    pub fn is_renewal_of(&self, other: &X509Ref) -> bool {
+        match self.subject_name().try_cmp(other.subject_name()) {
+            Ok(Ordering::Equal) => {}
+            _ => return false,
+        }
+        match (self.public_key(), other.public_key()) {
+            (Ok(a), Ok(b)) => a.public_eq(&b),
+            _ => false,
+        }
+    }
+
     /// Returns certificate version. If this certificate has no explicit version set, it defaults to
     /// version 1.
     ///
@@ -597,6 +715,87 @@ impl X509Ref {
         unsafe { cvt_n(ffi::X509_verify(self.as_ptr(), key.as_ptr())).map(|n| n != 0) }
     }
 
+    /// Verifies `signature` over `data` with the public key contained in this certificate.
+    ///
+    /// `digest` is the digest the signer used, which is unrelated to the digest the certificate
+    /// itself was signed with by its issuer.
+    ///
+    /// Returns `Ok(false)` if the signature does not match.
+    pub fn verify_signature(
+        &self,
+        digest: MessageDigest,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, ErrorStack> {
+        let key = self.public_key()?;
+        let mut verifier = Verifier::new(digest, &key)?;
+        verifier.update(data)?;
+        verifier.verify(signature)
+    }
+
+    /// Verifies `signature` over `data` with the public key contained in this certificate,
+    /// requiring the certificate to be valid at `at`.
+    ///
+    /// The validity period is checked first, so a certificate outside of it is rejected even if
+    /// the signature matches. `digest` is used as in [`verify_signature`].
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    ///
+    /// [`verify_signature`]: X509Ref::verify_signature
+    #[cfg(ossl102)]
+    pub fn verify_signature_at(
+        &self,
+        digest: MessageDigest,
+        data: &[u8],
+        signature: &[u8],
+        at: &Asn1TimeRef,
+    ) -> Result<(), VerifyAtError> {
+        if self.not_before().compare(at)? == Ordering::Greater {
+            return Err(VerifyAtError::NotYetValid);
+        }
+        if self.not_after().compare(at)? == Ordering::Less {
+            return Err(VerifyAtError::Expired);
+        }
+        if self.verify_signature(digest, data, signature)? {
+            Ok(())
+        } else {
+            Err(VerifyAtError::BadSignature)
+        }
+    }
+
+    /// Returns a copy of this certificate issued by a different issuer.
+    ///
+    /// Every field of the certificate, including its serial number, validity period and
+    /// extensions, is preserved except for the issuer name, which is replaced with
+    /// `issuer_name`. The copy is then signed with `issuer_key` using the digest `hash`.
+    ///
+    /// Extensions which refer to the issuer, such as the authority key identifier, are copied
+    /// unchanged.
+    #[corresponds(X509_sign)]
+    pub fn reissue<T>(
+        &self,
+        issuer_key: &PKeyRef<T>,
+        issuer_name: &X509NameRef,
+        hash: MessageDigest,
+    ) -> Result<X509, ErrorStack>
+    where
+        T: HasPrivate,
+    {
+        unsafe {
+            let x509 = X509::from_ptr(cvt_p(ffi::X509_dup(self.as_ptr()))?);
+            cvt(ffi::X509_set_issuer_name(
+                x509.as_ptr(),
+                issuer_name.as_ptr(),
+            ))?;
+            cvt(ffi::X509_sign(
+                x509.as_ptr(),
+                issuer_key.as_ptr(),
+                hash.as_ptr(),
+            ))?;
+            Ok(x509)
+        }
+    }
+
     /// Returns this certificate's serial number.
     #[corresponds(X509_get_serialNumber)]
     pub fn serial_number(&self) -> &Asn1IntegerRef {
@@ -615,6 +814,21 @@ impl X509Ref {
         ffi::PEM_write_bio_X509
     }
 
+    /// Writes the certificate to `w` as a PEM-encoded X509 structure.
+    ///
+    /// The encoding is written straight from the library's buffer rather than copied into an
+    /// intermediate `Vec` as [`to_pem`] does.
+    ///
+    /// [`to_pem`]: X509Ref::to_pem
+    #[corresponds(PEM_write_bio_X509)]
+    pub fn write_pem<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let bio = MemBio::new()?;
+        unsafe {
+            cvt(ffi::PEM_write_bio_X509(bio.as_ptr(), self.as_ptr()))?;
+        }
+        w.write_all(bio.get_buf())
+    }
+
     to_der! {
         /// Serializes the certificate into a DER-encoded X509 structure.
         #[corresponds(i2d_X509)]
@@ -622,6 +836,21 @@ impl X509Ref {
         ffi::i2d_X509
     }
 
+    /// Serializes the certificate's `TBSCertificate`, the portion covered by its signature.
+    ///
+    /// The bytes are taken from the certificate's original encoding, so they are exactly what the
+    /// issuer signed even if that encoding is not canonical DER.
+    #[corresponds(i2d_X509)]
+    pub fn tbs_certificate_der(&self) -> Result<Vec<u8>, ErrorStack> {
+        let der = self.to_der()?;
+        // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+        let bad_header = || new_error(ffi::ERR_LIB_ASN1, ffi::ASN1_R_BAD_OBJECT_HEADER);
+        let (outer_header, _) = der_header(&der).ok_or_else(bad_header)?;
+        let inner = &der[outer_header..];
+        let (header, len) = der_header(inner).ok_or_else(bad_header)?;
+        Ok(inner[..header + len].to_vec())
+    }
+
     to_pem! {
         /// Converts the certificate to human readable text.
         #[corresponds(X509_print)]
@@ -685,7 +914,8 @@ impl X509 {
     from_pem! {
         /// Deserializes a PEM-encoded X509 structure.
         ///
-        /// The input should have a header of `-----BEGIN CERTIFICATE-----`.
+        /// The input should have a header of `-----BEGIN CERTIFICATE-----`. Like
+        /// [`from_der`](X509::from_der), this never verifies a signature or uses key material.
         #[corresponds(PEM_read_bio_X509)]
         from_pem,
         X509,
@@ -694,6 +924,10 @@ impl X509 {
 
     from_der! {
         /// Deserializes a DER-encoded X509 structure.
+        ///
+        /// This only decodes the structure. No signature is verified and no key material is used,
+        /// so arbitrary untrusted input may be passed in; malformed input returns an error rather
+        /// than panicking. This makes it suitable as a parse-only fuzzing entry point.
         #[corresponds(d2i_X509)]
         from_der,
         X509,
@@ -808,6 +1042,190 @@ impl PartialEq<X509Ref> for X509 {
 
 impl Eq for X509 {}
 
+/// A leaf certificate together with the chain of certificates which issued it.
+///
+/// The chain is ordered starting with the issuer of the leaf, so it can be handed directly to
+/// [`SslContextBuilder::add_extra_chain_cert`].
+///
+/// [`SslContextBuilder::add_extra_chain_cert`]: crate::ssl::SslContextBuilder::add_extra_chain_cert
+#[derive(Clone, Debug)]
+pub struct CertBundle {
+    leaf: X509,
+    chain: Vec<X509>,
+}
+
+impl CertBundle {
+    /// Creates a bundle from an unordered set of certificates.
+    ///
+    /// The leaf is identified as the only certificate which did not issue any other certificate
+    /// in the set. The remaining certificates are then ordered by following the issuer of each
+    /// certificate, and must all be part of that single chain.
+    pub fn new(certs: Vec<X509>) -> Result<CertBundle, CertBundleError> {
+        let mut leaves = certs.iter().enumerate().filter(|(i, cert)| {
+            !certs
+                .iter()
+                .enumerate()
+                .any(|(j, other)| *i != j && cert.issued(other) == X509VerifyResult::OK)
+        });
+        let leaf = match (leaves.next(), leaves.next()) {
+            (Some((i, _)), None) => i,
+            (None, _) => return Err(CertBundleError::NoLeaf),
+            (Some(_), Some(_)) => return Err(CertBundleError::MultipleLeaves),
+        };
+
+        let mut remaining = certs;
+        let leaf = remaining.remove(leaf);
+        let mut chain = vec![];
+        loop {
+            let current = chain.last().unwrap_or(&leaf);
+            if current.issued(current) == X509VerifyResult::OK {
+                break;
+            }
+            match remaining
+                .iter()
+                .position(|cert| cert.issued(current) == X509VerifyResult::OK)
+            {
+                Some(i) => chain.push(remaining.remove(i)),
+                None => break,
+            }
+        }
+
+        if !remaining.is_empty() {
+            return Err(CertBundleError::UnrelatedCertificate);
+        }
+
+        Ok(CertBundle { leaf, chain })
+    }
+
+    /// Creates a bundle from a sequence of PEM-encoded certificates, in any order.
+    pub fn from_pem(pem: &[u8]) -> Result<CertBundle, CertBundleError> {
+        let certs = X509::stack_from_pem(pem)?;
+        CertBundle::new(certs)
+    }
+
+    /// Returns the leaf certificate.
+    pub fn leaf(&self) -> &X509Ref {
+        &self.leaf
+    }
+
+    /// Returns the chain of issuers of the leaf, starting with its direct issuer.
+    pub fn chain(&self) -> &[X509] {
+        &self.chain
+    }
+
+    /// Consumes the bundle, returning the leaf and its chain.
+    pub fn into_parts(self) -> (X509, Vec<X509>) {
+        (self.leaf, self.chain)
+    }
+}
+
+/// An error constructing a [`CertBundle`].
+#[derive(Debug)]
+pub enum CertBundleError {
+    /// The certificates could not be parsed.
+    Parse(ErrorStack),
+    /// Every certificate issued another certificate in the bundle.
+    NoLeaf,
+    /// More than one certificate did not issue any other certificate in the bundle.
+    MultipleLeaves,
+    /// A certificate is not part of the chain issuing the leaf.
+    UnrelatedCertificate,
+}
+
+impl fmt::Display for CertBundleError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertBundleError::Parse(e) => fmt::Display::fmt(e, fmt),
+            CertBundleError::NoLeaf => fmt.write_str("no leaf certificate in bundle"),
+            CertBundleError::MultipleLeaves => {
+                fmt.write_str("multiple leaf certificates in bundle")
+            }
+            CertBundleError::UnrelatedCertificate => {
+                fmt.write_str("certificate in bundle is not part of the leaf's chain")
+            }
+        }
+    }
+}
+
+impl Error for CertBundleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CertBundleError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for CertBundleError {
+    fn from(e: ErrorStack) -> CertBundleError {
+        CertBundleError::Parse(e)
+    }
+}
+
+/// An error returned by [`X509Ref::verify_signature_at`].
+#[derive(Debug)]
+pub enum VerifyAtError {
+    /// The certificate's validity period starts after the given time.
+    NotYetValid,
+    /// The certificate's validity period ends before the given time.
+    Expired,
+    /// The signature does not match the data.
+    BadSignature,
+    /// An error occurred while checking the certificate or the signature.
+    Other(ErrorStack),
+}
+
+impl fmt::Display for VerifyAtError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyAtError::NotYetValid => fmt.write_str("certificate is not yet valid"),
+            VerifyAtError::Expired => fmt.write_str("certificate has expired"),
+            VerifyAtError::BadSignature => fmt.write_str("signature verification failed"),
+            VerifyAtError::Other(e) => fmt::Display::fmt(e, fmt),
+        }
+    }
+}
+
+impl Error for VerifyAtError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VerifyAtError::Other(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for VerifyAtError {
+    fn from(e: ErrorStack) -> VerifyAtError {
+        VerifyAtError::Other(e)
+    }
+}
+
+/// A certificate signature algorithm, as returned by [`X509Ref::signature_algorithm_kind`].
+#[derive(Debug, Copy, Clone)]
+pub enum SignatureAlgorithm<'a> {
+    /// SM2 with SM3.
+    Sm2WithSm3,
+    /// RSA PKCS#1 v1.5 with SM3.
+    RsaWithSm3,
+    /// RSA PKCS#1 v1.5 with SHA-1.
+    RsaWithSha1,
+    /// RSA PKCS#1 v1.5 with SHA-256.
+    RsaWithSha256,
+    /// RSA PKCS#1 v1.5 with SHA-384.
+    RsaWithSha384,
+    /// RSA PKCS#1 v1.5 with SHA-512.
+    RsaWithSha512,
+    /// ECDSA with SHA-256.
+    EcdsaWithSha256,
+    /// ECDSA with SHA-384.
+    EcdsaWithSha384,
+    /// ECDSA with SHA-512.
+    EcdsaWithSha512,
+    /// Any other algorithm, identified by its OID.
+    Other(&'a Asn1ObjectRef),
+}
+
 /// A context object required to construct certain `X509` extension values.
 pub struct X509v3Context<'a>(ffi::X509V3_CTX, PhantomData<(&'a X509Ref, &'a ConfRef)>);
 
@@ -2036,6 +2454,70 @@ impl GeneralNameRef {
             Some(slice::from_raw_parts(ptr as *const u8, len as usize))
         }
     }
+
+    /// Returns the contents of this `GeneralName` if it is an `otherName`.
+    ///
+    /// The value is returned as the DER encoding of the `[0] EXPLICIT` content.
+    fn other_name(&self) -> Option<(Asn1Object, Vec<u8>)> {
+        unsafe {
+            if (*self.as_ptr()).type_ != ffi::GEN_OTHERNAME {
+                return None;
+            }
+
+            #[cfg(boringssl)]
+            let d = (*self.as_ptr()).d.ptr;
+            #[cfg(not(boringssl))]
+            let d = (*self.as_ptr()).d;
+
+            let other = d as *const ffi::OTHERNAME;
+            let oid = Asn1Object::from_ptr(cvt_p(ffi::OBJ_dup((*other).type_id)).ok()?);
+
+            let len = cvt(ffi::i2d_ASN1_TYPE((*other).value, ptr::null_mut())).ok()?;
+            let mut buf = vec![0; len as usize];
+            let mut p = buf.as_mut_ptr();
+            cvt(ffi::i2d_ASN1_TYPE((*other).value, &mut p)).ok()?;
+
+            Some((oid, buf))
+        }
+    }
+
+    /// Converts this `GeneralName` into a [`SubjectAltName`].
+    ///
+    /// Returns `None` for kinds that `SubjectAltName` does not represent, and for
+    /// `iPAddress` entries that are neither 4 nor 16 bytes long.
+    pub fn to_subject_alt_name(&self) -> Option<SubjectAltName> {
+        if let Some(dns) = self.dnsname() {
+            Some(SubjectAltName::Dns(dns.to_string()))
+        } else if let Some(email) = self.email() {
+            Some(SubjectAltName::Email(email.to_string()))
+        } else if let Some(uri) = self.uri() {
+            Some(SubjectAltName::Uri(uri.to_string()))
+        } else if let Some(ip) = self.ipaddress() {
+            <[u8; 16]>::try_from(ip)
+                .map(IpAddr::from)
+                .or_else(|_| <[u8; 4]>::try_from(ip).map(IpAddr::from))
+                .ok()
+                .map(SubjectAltName::Ip)
+        } else {
+            self.other_name()
+                .map(|(oid, value)| SubjectAltName::OtherName(oid, value))
+        }
+    }
+}
+
+/// A typed subject alternative name entry.
+#[derive(Debug)]
+pub enum SubjectAltName {
+    /// A `dNSName` entry.
+    Dns(String),
+    /// An `iPAddress` entry.
+    Ip(IpAddr),
+    /// An `rfc822Name` entry.
+    Email(String),
+    /// A `uniformResourceIdentifier` entry.
+    Uri(String),
+    /// An `otherName` entry: its type identifier and the DER encoding of its value.
+    OtherName(Asn1Object, Vec<u8>),
 }
 
 impl fmt::Debug for GeneralNameRef {
@@ -258,12 +258,33 @@ foreign_type_and_impl_send_sync! {
     pub struct X509StoreRef;
 }
 
+impl X509Store {
+    /// Creates a store trusting the single certificate `cert`.
+    pub fn with_root(cert: X509) -> Result<X509Store, ErrorStack> {
+        let mut builder = X509StoreBuilder::new()?;
+        builder.add_cert(cert)?;
+        Ok(builder.build())
+    }
+}
+
 impl X509StoreRef {
     /// Get a reference to the cache of certificates in this store.
     #[corresponds(X509_STORE_get0_objects)]
     pub fn objects(&self) -> &StackRef<X509Object> {
         unsafe { StackRef::from_ptr(X509_STORE_get0_objects(self.as_ptr())) }
     }
+
+    /// Returns the certificates in the store's cache.
+    ///
+    /// Certificates that are only reachable through a lookup, such as a hashed directory, are
+    /// not included until they have been loaded.
+    pub fn roots(&self) -> Vec<X509> {
+        self.objects()
+            .iter()
+            .filter_map(|o| o.x509())
+            .map(|c| c.to_owned())
+            .collect()
+    }
 }
 
 cfg_if! {
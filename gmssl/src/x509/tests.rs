@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::net::{IpAddr, Ipv6Addr};
 
 use crate::asn1::Asn1Time;
 use crate::bn::{BigNum, MsbOption};
@@ -6,6 +7,7 @@ use crate::hash::MessageDigest;
 use crate::nid::Nid;
 use crate::pkey::{PKey, Private};
 use crate::rsa::Rsa;
+use crate::sign::{Signer, Verifier};
 #[cfg(not(boringssl))]
 use crate::ssl::SslFiletype;
 use crate::stack::Stack;
@@ -15,18 +17,19 @@ use crate::x509::extension::{
 };
 #[cfg(not(boringssl))]
 use crate::x509::store::X509Lookup;
-use crate::x509::store::X509StoreBuilder;
+use crate::x509::store::{X509Store, X509StoreBuilder};
 #[cfg(any(ossl102, libressl261))]
 use crate::x509::verify::{X509VerifyFlags, X509VerifyParam};
-#[cfg(ossl102)]
-use crate::x509::X509PurposeId;
 #[cfg(any(ossl102, libressl261))]
 use crate::x509::X509PurposeRef;
-#[cfg(ossl110)]
-use crate::x509::{CrlReason, X509Builder};
 use crate::x509::{
-    CrlStatus, X509Crl, X509Extension, X509Name, X509Req, X509StoreContext, X509VerifyResult, X509,
+    CertBundle, CertBundleError, CrlStatus, SignatureAlgorithm, SubjectAltName, X509Crl,
+    X509Extension, X509Name, X509Req, X509StoreContext, X509VerifyResult, X509,
 };
+#[cfg(ossl110)]
+use crate::x509::{CrlReason, X509Builder};
+#[cfg(ossl102)]
+use crate::x509::{VerifyAtError, X509PurposeId};
 use hex::{self, FromHex};
 #[cfg(any(ossl102, libressl261))]
 use libc::time_t;
@@ -86,6 +89,17 @@ fn test_save_der() {
     assert!(!der.is_empty());
 }
 
+#[test]
+fn test_write_pem() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+
+    let mut pem = vec![];
+    cert.write_pem(&mut pem).unwrap();
+    assert_eq!(pem, cert.to_pem().unwrap());
+    assert!(X509::from_pem(&pem).unwrap() == cert);
+}
+
 #[test]
 fn test_subject_read_cn() {
     let cert = include_bytes!("../../test/cert.pem");
@@ -198,6 +212,24 @@ fn test_subject_alt_name_iter() {
     assert!(subject_alt_names_iter.next().is_none());
 }
 
+#[test]
+fn test_typed_subject_alt_names() {
+    let cert = include_bytes!("../../test/alt_name_cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+
+    let names = cert.typed_subject_alt_names();
+    assert_eq!(5, names.len());
+    assert!(matches!(&names[0], SubjectAltName::Dns(d) if d == "example.com"));
+    assert!(matches!(names[1], SubjectAltName::Ip(ip) if ip == IpAddr::from([127, 0, 0, 1])));
+    assert!(matches!(names[2], SubjectAltName::Ip(ip) if ip == IpAddr::from(Ipv6Addr::LOCALHOST)));
+    assert!(matches!(&names[3], SubjectAltName::Email(e) if e == "test@example.com"));
+    assert!(matches!(&names[4], SubjectAltName::Uri(u) if u == "http://www.example.com"));
+
+    let cert = include_bytes!("../../test/root-ca.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    assert!(cert.typed_subject_alt_names().is_empty());
+}
+
 #[test]
 fn test_aia_ca_issuer() {
     // With AIA
@@ -455,6 +487,56 @@ fn test_verify_cert() {
         .unwrap());
 }
 
+#[test]
+fn test_store_with_root() {
+    // Built here rather than loaded from test/ so that the validity period never lapses.
+    fn issue(cn: &str, key: &PKey<Private>, issuer: Option<(&X509, &PKey<Private>)>) -> X509 {
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder.set_pubkey(key).unwrap();
+        match issuer {
+            Some((ca, ca_key)) => {
+                builder.set_issuer_name(ca.subject_name()).unwrap();
+                builder.sign(ca_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+                builder.sign(key, MessageDigest::sha256()).unwrap();
+            }
+        }
+        builder.build()
+    }
+
+    let ca_key = pkey();
+    let ca = issue("store root", &ca_key, None);
+    let cert = issue("store leaf", &pkey(), Some((&ca, &ca_key)));
+    let chain = Stack::new().unwrap();
+
+    let store = X509Store::with_root(ca.clone()).unwrap();
+    let roots = store.roots();
+    assert_eq!(roots.len(), 1);
+    assert!(roots[0] == ca);
+
+    let mut context = X509StoreContext::new().unwrap();
+    assert!(context
+        .init(&store, &cert, &chain, |c| c.verify_cert())
+        .unwrap());
+}
+
 #[test]
 fn test_verify_fails() {
     let cert = include_bytes!("../../test/cert.pem");
@@ -1051,3 +1133,348 @@ fn test_dist_point_null() {
     let cert = X509::from_pem(cert).unwrap();
     assert!(cert.crl_distribution_points().is_none());
 }
+
+#[test]
+fn test_parse_malformed_der() {
+    let der = X509::from_pem(include_bytes!("../../test/cert.pem"))
+        .unwrap()
+        .to_der()
+        .unwrap();
+
+    assert!(X509::from_der(&[]).is_err());
+    assert!(X509::from_der(&[0x30]).is_err());
+    // An outer SEQUENCE claiming far more content than is present.
+    assert!(X509::from_der(&[0x30, 0x84, 0x7f, 0xff, 0xff, 0xff, 0x30]).is_err());
+    for len in [1, der.len() / 2, der.len() - 1] {
+        assert!(X509::from_der(&der[..len]).is_err());
+    }
+
+    let mut corrupt = der;
+    corrupt[1] ^= 0xff;
+    assert!(X509::from_der(&corrupt).is_err());
+}
+
+#[test]
+fn test_parse_malformed_pem() {
+    let pem = include_bytes!("../../test/cert.pem");
+
+    assert!(X509::from_pem(b"").is_err());
+    assert!(X509::from_pem(&pem[..pem.len() / 2]).is_err());
+    assert!(
+        X509::from_pem(b"-----BEGIN CERTIFICATE-----\n!!!!\n-----END CERTIFICATE-----\n").is_err()
+    );
+    assert!(X509::stack_from_pem(
+        b"-----BEGIN CERTIFICATE-----\nMAA=\n-----END CERTIFICATE-----\n"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_verify_signature() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let key = PKey::private_key_from_pem(include_bytes!("../../test/key.pem")).unwrap();
+    let data = b"hello, world!";
+
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(data).unwrap();
+    let signature = signer.sign_to_vec().unwrap();
+
+    assert!(cert
+        .verify_signature(MessageDigest::sha256(), data, &signature)
+        .unwrap());
+    assert!(!cert
+        .verify_signature(MessageDigest::sha256(), b"hello, world?", &signature)
+        .unwrap());
+}
+
+#[test]
+fn test_signature_algorithm_kind() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    assert!(matches!(
+        cert.signature_algorithm_kind(),
+        SignatureAlgorithm::RsaWithSha256
+    ));
+
+    #[cfg(ossl111)]
+    {
+        let cert = X509::from_pem(include_bytes!("../../test/sm2_cert.pem")).unwrap();
+        assert!(matches!(
+            cert.signature_algorithm_kind(),
+            SignatureAlgorithm::Sm2WithSm3
+        ));
+    }
+}
+
+#[test]
+#[cfg(all(ossl111, not(osslconf = "OPENSSL_NO_SM2")))]
+fn test_sm2_public_key_point() {
+    let cert = X509::from_pem(include_bytes!("../../test/sm2_cert.pem")).unwrap();
+    let point = cert.sm2_public_key_point().unwrap();
+    assert_eq!(
+        hex::encode(&point[..]),
+        "049e4e6e97863671c980f50f22be5402ae204165344e5382c8a62dc11ffe92ed8d\
+         99c0c3a1b44fc6fe38385e2f0b4980a559d8b3dedb26f2129cf19047a02c51f8"
+    );
+
+    #[cfg(ossl300)]
+    {
+        let key = crate::sm2::Sm2PublicKey::from_bytes(&point).unwrap();
+        assert!(!crate::sm2::encrypt(&key, b"hello").unwrap().is_empty());
+    }
+
+    let rsa = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    assert!(rsa.sm2_public_key_point().is_err());
+}
+
+#[test]
+fn test_verify_signature_wrong_digest() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let key = PKey::private_key_from_pem(include_bytes!("../../test/key.pem")).unwrap();
+    let data = b"hello, world!";
+
+    let mut signer = Signer::new(MessageDigest::sha384(), &key).unwrap();
+    signer.update(data).unwrap();
+    let signature = signer.sign_to_vec().unwrap();
+
+    // cert.pem is signed by its issuer with SHA-256, which must not be assumed here.
+    assert!(!cert
+        .verify_signature(MessageDigest::sha256(), data, &signature)
+        .unwrap());
+    assert!(cert
+        .verify_signature(MessageDigest::sha384(), data, &signature)
+        .unwrap());
+}
+
+#[test]
+#[cfg(ossl102)]
+fn test_verify_signature_at() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let key = PKey::private_key_from_pem(include_bytes!("../../test/key.pem")).unwrap();
+    let data = b"hello, world!";
+
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(data).unwrap();
+    let signature = signer.sign_to_vec().unwrap();
+
+    let valid = Asn1Time::from_str("20200101000000Z").unwrap();
+    cert.verify_signature_at(MessageDigest::sha256(), data, &signature, &valid)
+        .unwrap();
+    assert!(matches!(
+        cert.verify_signature_at(
+            MessageDigest::sha256(),
+            b"hello, world?",
+            &signature,
+            &valid
+        ),
+        Err(VerifyAtError::BadSignature)
+    ));
+
+    let before = Asn1Time::from_str("20150101000000Z").unwrap();
+    assert!(matches!(
+        cert.verify_signature_at(MessageDigest::sha256(), data, &signature, &before),
+        Err(VerifyAtError::NotYetValid)
+    ));
+
+    let after = Asn1Time::from_str("20270101000000Z").unwrap();
+    assert!(matches!(
+        cert.verify_signature_at(MessageDigest::sha256(), data, &signature, &after),
+        Err(VerifyAtError::Expired)
+    ));
+}
+
+#[test]
+fn test_tbs_certificate_der() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = ca.public_key().unwrap();
+    let der = cert.to_der().unwrap();
+
+    let tbs = cert.tbs_certificate_der().unwrap();
+    assert_eq!(&der[4..4 + tbs.len()], &tbs[..]);
+    assert_eq!(cert.to_der().unwrap(), der);
+
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &ca_key).unwrap();
+    verifier.update(&tbs).unwrap();
+    assert!(verifier.verify(cert.signature().as_slice()).unwrap());
+}
+
+#[test]
+fn test_reissue() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let old_key = PKey::private_key_from_pem(include_bytes!("../../test/root-ca.key")).unwrap();
+    let new_key = pkey();
+
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "new issuer")
+        .unwrap();
+    let name = name.build();
+
+    let reissued = cert
+        .reissue(&new_key, &name, MessageDigest::sha256())
+        .unwrap();
+
+    assert!(cert.verify(&old_key).unwrap());
+    assert!(reissued.verify(&new_key).unwrap());
+    assert!(!reissued.verify(&old_key).unwrap());
+
+    assert_eq!(
+        reissued.issuer_name().to_der().unwrap(),
+        name.to_der().unwrap()
+    );
+    assert_eq!(
+        reissued.subject_name().to_der().unwrap(),
+        cert.subject_name().to_der().unwrap()
+    );
+    assert_eq!(
+        reissued.serial_number().to_bn().unwrap(),
+        cert.serial_number().to_bn().unwrap()
+    );
+    assert_eq!(reissued.not_before(), cert.not_before());
+    assert_eq!(reissued.not_after(), cert.not_after());
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_basic_constraints_accessors() {
+    let root = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    assert!(root.is_ca());
+    assert_eq!(root.path_len_constraint(), None);
+
+    let intermediate = X509::from_pem(include_bytes!("../../test/intermediate-ca.pem")).unwrap();
+    assert!(intermediate.is_ca());
+    assert_eq!(intermediate.path_len_constraint(), Some(0));
+
+    let leaf = X509::from_pem(include_bytes!("../../test/leaf.pem")).unwrap();
+    assert!(!leaf.is_ca());
+    assert_eq!(leaf.path_len_constraint(), None);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_subject_key_id() {
+    let root = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let key = root.public_key().unwrap();
+    let expected = hex::decode("6cd3a503ab0d5f2cc98d8a9c88a78877b837fd9a").unwrap();
+
+    assert_eq!(root.subject_key_id(), Some(&expected[..]));
+    assert_eq!(
+        &*key.key_identifier(MessageDigest::sha1()).unwrap(),
+        &expected[..]
+    );
+
+    let leaf = X509::from_pem(include_bytes!("../../test/leaf.pem")).unwrap();
+    assert_eq!(leaf.subject_key_id(), None);
+}
+
+#[test]
+fn test_is_self_signed() {
+    let root = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    assert!(root.is_self_signed());
+
+    let intermediate = X509::from_pem(include_bytes!("../../test/intermediate-ca.pem")).unwrap();
+    assert!(!intermediate.is_self_signed());
+
+    let pkey = pkey();
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "leaf.example.com")
+        .unwrap();
+    let name = name.build();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(&name).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+        .unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let leaf = builder.build();
+
+    assert!(leaf.is_self_signed());
+    #[cfg(ossl110)]
+    assert!(!leaf.is_ca());
+}
+
+#[test]
+fn test_cert_bundle() {
+    let root = include_bytes!("../../test/root-ca.pem");
+    let intermediate = include_bytes!("../../test/intermediate-ca.pem");
+    let leaf = include_bytes!("../../test/leaf.pem");
+    let pem = [&root[..], &leaf[..], &intermediate[..]].concat();
+
+    let bundle = CertBundle::from_pem(&pem).unwrap();
+    assert!(bundle.leaf() == &X509::from_pem(leaf).unwrap());
+    assert_eq!(
+        bundle.chain(),
+        &[
+            X509::from_pem(intermediate).unwrap(),
+            X509::from_pem(root).unwrap()
+        ][..]
+    );
+}
+
+#[test]
+fn test_cert_bundle_invalid() {
+    let root = include_bytes!("../../test/root-ca.pem");
+    let leaf = include_bytes!("../../test/leaf.pem");
+    let cert = include_bytes!("../../test/cert.pem");
+
+    let pem = [&leaf[..], &cert[..], &root[..]].concat();
+    assert!(matches!(
+        CertBundle::from_pem(&pem),
+        Err(CertBundleError::MultipleLeaves)
+    ));
+
+    let pem = [&leaf[..], &root[..]].concat();
+    assert!(matches!(
+        CertBundle::from_pem(&pem),
+        Err(CertBundleError::MultipleLeaves)
+    ));
+
+    assert!(matches!(
+        CertBundle::from_pem(b"not a certificate"),
+        Err(CertBundleError::NoLeaf)
+    ));
+}
+
+#[test]
+fn test_is_renewal_of() {
+    fn cert(pkey: &PKey<Private>, cn: &str, serial: u32, days: u32) -> X509 {
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(serial).unwrap().to_asn1_integer().unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(days).unwrap())
+            .unwrap();
+        builder.set_pubkey(pkey).unwrap();
+        builder.sign(pkey, MessageDigest::sha256()).unwrap();
+        builder.build()
+    }
+
+    let key = pkey();
+    let other_key = pkey();
+    let old = cert(&key, "renew.example.com", 1, 30);
+    let new = cert(&key, "renew.example.com", 2, 365);
+    assert!(new.is_renewal_of(&old));
+    assert!(old.is_renewal_of(&new));
+
+    let rekeyed = cert(&other_key, "renew.example.com", 3, 365);
+    assert!(!rekeyed.is_renewal_of(&old));
+
+    let renamed = cert(&key, "other.example.com", 4, 365);
+    assert!(!renamed.is_renewal_of(&old));
+}
@@ -0,0 +1,50 @@
+//! `set_entropy_source` replaces the generator for the whole process, so this test lives in its
+//! own binary where no other test can draw from it concurrently.
+#![cfg(all(ossl110, not(osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
+
+use gmssl::ec::{EcGroup, EcKey};
+use gmssl::nid::Nid;
+use gmssl::rand::{clear_entropy_source, rand_bytes, set_entropy_source};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+static STATE: AtomicU64 = AtomicU64::new(SEED);
+
+fn reset() {
+    STATE.store(SEED, Ordering::SeqCst);
+}
+
+fn xorshift(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        let mut x = STATE.load(Ordering::SeqCst);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        STATE.store(x, Ordering::SeqCst);
+        *b = x as u8;
+    }
+}
+
+#[test]
+fn entropy_source() {
+    set_entropy_source(xorshift).unwrap();
+
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    reset();
+    let key1 = EcKey::generate(&group).unwrap();
+    reset();
+    let key2 = EcKey::generate(&group).unwrap();
+    reset();
+    let mut buf = [0; 16];
+    rand_bytes(&mut buf).unwrap();
+
+    clear_entropy_source().unwrap();
+
+    assert_eq!(key1.private_key(), key2.private_key());
+
+    let mut buf2 = [0; 16];
+    reset();
+    rand_bytes(&mut buf2).unwrap();
+    assert_ne!(buf, buf2);
+}
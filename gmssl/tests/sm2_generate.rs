@@ -0,0 +1,33 @@
+//! `set_entropy_source` replaces the generator for the whole process, so this test lives in its
+//! own binary where no other test can draw from it concurrently.
+#![cfg(all(
+    ossl111,
+    not(osslconf = "OPENSSL_NO_DEPRECATED_3_0"),
+    not(osslconf = "OPENSSL_NO_SM2")
+))]
+
+use gmssl::rand::{clear_entropy_source, set_entropy_source};
+use gmssl::sm2::Sm2Key;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SERVED: AtomicUsize = AtomicUsize::new(0);
+
+// Serves an all-zero first candidate scalar, then a fixed pattern.
+fn zero_then_pattern(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        let n = SERVED.fetch_add(1, Ordering::SeqCst);
+        *b = if n < 32 { 0 } else { (n * 7 + 1) as u8 };
+    }
+}
+
+#[test]
+fn generate_retries_zero_scalar() {
+    set_entropy_source(zero_then_pattern).unwrap();
+    let key = Sm2Key::generate_checked();
+    let served = SERVED.load(Ordering::SeqCst);
+    clear_entropy_source().unwrap();
+
+    let key = key.unwrap();
+    assert!(served >= 64);
+    assert!(key.ec_key().private_key().num_bits() > 0);
+}